@@ -0,0 +1,24 @@
+use std::fmt;
+
+/// `CliError` denotes errors that can occur when parsing the command line arguments given to
+/// `examtrainer`.
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    MissingValue(String),
+    UnknownFlag(String),
+    UnrecognisedCommand(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingValue(flag) => write!(f, "Flag '{}' requires a value", flag),
+            Self::UnknownFlag(flag) => write!(f, "Unknown flag: '{}'", flag),
+            Self::UnrecognisedCommand(command) => {
+                write!(f, "Unrecognised command: '{}'", command)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
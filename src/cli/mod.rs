@@ -0,0 +1,175 @@
+//! Command line argument parsing for `examtrainer`
+//!
+//! `examtrainer` accepts an optional `--config <path>` flag, followed by an optional command.
+//! When no command is given, the interactive main menu is started. The commands mirror those
+//! available in the main menu, so that they can be used directly from scripts and aliases:
+//! * `init` - Create the config file and directories, without asking first
+//! * `list questions` - List all questions
+//! * `list exams` - List all exams
+//! * `question <name>` - Start single question mode for the question `<name>`
+//! * `exam <name>` - Start the exam `<name>`
+//! * `grade <name>` - Grade the current submission for the question `<name>`, without any
+//!   prompts
+//! * `config` - Display the configuration in use
+//! * `doctor` - Check that the compilers, frameworks, directories and programs needed for grading
+//!   are available
+//! * `help` - Show usage information
+//!
+//! `--yes` or `--no` answer every question asked while reading the config file (such as whether
+//! a missing directory should be created), so that `examtrainer` never waits for input.
+
+pub mod error;
+
+pub use error::CliError;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    MainMenu,
+    Init,
+    ListQuestions,
+    ListExams,
+    Question(String),
+    Exam(String),
+    Grade(String),
+    Config,
+    Doctor,
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Args {
+    pub config: Option<String>,
+    /// The answer given with `--yes` or `--no`, if any
+    pub answer: Option<bool>,
+    pub command: Command,
+}
+
+impl Args {
+    /// Parse command line arguments into [`Args`]
+    ///
+    /// The first element of `args` is expected to be the program name (as given by
+    /// [`std::env::args`]), and is skipped.
+    pub fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut config = None;
+        let mut answer = None;
+        let mut words = Vec::new();
+        let mut iter = args.iter().skip(1);
+
+        while let Some(arg) = iter.next() {
+            match &arg[..] {
+                "-c" | "--config" => {
+                    let path = iter
+                        .next()
+                        .ok_or_else(|| CliError::MissingValue(arg.clone()))?;
+                    config = Some(path.clone());
+                }
+                "-h" | "--help" => words.push("help"),
+                "-y" | "--yes" => answer = Some(true),
+                "--no" => answer = Some(false),
+                flag if flag.starts_with("--config=") => {
+                    config = Some(flag["--config=".len()..].to_string());
+                }
+                flag if flag.starts_with('-') => return Err(CliError::UnknownFlag(flag.into())),
+                word => words.push(word),
+            }
+        }
+
+        let command = match words.as_slice() {
+            [] => Command::MainMenu,
+            ["init"] => Command::Init,
+            ["list", "questions"] => Command::ListQuestions,
+            ["list", "exams"] => Command::ListExams,
+            ["question", name] => Command::Question(name.to_string()),
+            ["exam", name] => Command::Exam(name.to_string()),
+            ["grade", name] => Command::Grade(name.to_string()),
+            ["config"] => Command::Config,
+            ["doctor"] => Command::Doctor,
+            ["help"] => Command::Help,
+            _ => return Err(CliError::UnrecognisedCommand(words.join(" "))),
+        };
+        Ok(Self {
+            config,
+            answer,
+            command,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        std::iter::once("examtrainer")
+            .chain(args.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn no_arguments() -> Result<(), CliError> {
+        let args = Args::parse(&to_args(&[]))?;
+        assert_eq!(args.config, None);
+        assert_eq!(args.command, Command::MainMenu);
+        Ok(())
+    }
+
+    #[test]
+    fn config_flag() -> Result<(), CliError> {
+        let args = Args::parse(&to_args(&["--config", "tst/resources/test_config1.toml"]))?;
+        assert_eq!(
+            args.config,
+            Some("tst/resources/test_config1.toml".to_string())
+        );
+        assert_eq!(args.command, Command::MainMenu);
+        let args = Args::parse(&to_args(&["list", "exams", "--config=config.toml"]))?;
+        assert_eq!(args.config, Some("config.toml".to_string()));
+        assert_eq!(args.command, Command::ListExams);
+        Ok(())
+    }
+
+    #[test]
+    fn subcommands() -> Result<(), CliError> {
+        let args = Args::parse(&to_args(&["-c", "config.toml", "question", "aff_a"]))?;
+        assert_eq!(args.config, Some("config.toml".to_string()));
+        assert_eq!(args.command, Command::Question("aff_a".into()));
+        let args = Args::parse(&to_args(&["exam", "Exam_prototype"]))?;
+        assert_eq!(args.command, Command::Exam("Exam_prototype".into()));
+        let args = Args::parse(&to_args(&["grade", "aff_a"]))?;
+        assert_eq!(args.command, Command::Grade("aff_a".into()));
+        let args = Args::parse(&to_args(&["list", "questions"]))?;
+        assert_eq!(args.command, Command::ListQuestions);
+        let args = Args::parse(&to_args(&["doctor"]))?;
+        assert_eq!(args.command, Command::Doctor);
+        Ok(())
+    }
+
+    #[test]
+    fn answer_flags() -> Result<(), CliError> {
+        let args = Args::parse(&to_args(&["init"]))?;
+        assert_eq!(args.answer, None);
+        assert_eq!(args.command, Command::Init);
+        let args = Args::parse(&to_args(&["--yes", "init"]))?;
+        assert_eq!(args.answer, Some(true));
+        let args = Args::parse(&to_args(&["list", "exams", "--no"]))?;
+        assert_eq!(args.answer, Some(false));
+        assert_eq!(args.command, Command::ListExams);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert_eq!(
+            Args::parse(&to_args(&["--config"])),
+            Err(CliError::MissingValue("--config".into()))
+        );
+        assert_eq!(
+            Args::parse(&to_args(&["--verbose"])),
+            Err(CliError::UnknownFlag("--verbose".into()))
+        );
+        assert_eq!(
+            Args::parse(&to_args(&["question"])),
+            Err(CliError::UnrecognisedCommand("question".into()))
+        );
+    }
+}
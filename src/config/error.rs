@@ -9,6 +9,7 @@ pub enum ConfigError {
     NoConfigDirectory,
     NoExamTrainerDirectory,
     ConfigFileNotFound,
+    NoConfigFile(String),
     InvalidConfigFile(toml_parse::de::Error),
     NoQuestionDirectory(String),
     NoExamDirectory(String),
@@ -16,6 +17,10 @@ pub enum ConfigError {
     IO(io::Error),
     InvalidFramework,
     InvalidFrameworkDir(String),
+    InvalidPath(String),
+    UndefinedVariable(String),
+    PkgConfig(String, String),
+    InvalidTimeout(f64),
 }
 
 impl fmt::Display for ConfigError {
@@ -27,6 +32,11 @@ impl fmt::Display for ConfigError {
                 write!(f, "Could not find examtrainer/ directory in $HOME/.config/")
             }
             Self::ConfigFileNotFound => write!(f, "Unable to find/open config file"),
+            Self::NoConfigFile(path) => write!(
+                f,
+                "Config file '{}' does not exist (run `examtrainer --config {} init` to create it)",
+                path, path
+            ),
             Self::InvalidConfigFile(toml_e) => write!(f, "Error parsing config: {}", toml_e),
             Self::NoQuestionDirectory(d) => {
                 write!(f, "Question directory '{}' could not be found", d)
@@ -40,6 +50,28 @@ impl fmt::Display for ConfigError {
             Self::InvalidFrameworkDir(dir) => {
                 write!(f, "Invalid framework directory in Config: {}", dir)
             }
+            Self::PkgConfig(package, reason) => {
+                write!(
+                    f,
+                    "pkg-config failed for framework package '{}': {}",
+                    package, reason
+                )
+            }
+            Self::InvalidTimeout(timeout) => {
+                write!(
+                    f,
+                    "Timeout must be a positive number of seconds: {}",
+                    timeout
+                )
+            }
+            Self::InvalidPath(path) => write!(f, "Invalid path in Config: {}", path),
+            Self::UndefinedVariable(var) => {
+                write!(
+                    f,
+                    "Environment variable '{}' used in Config is not set",
+                    var
+                )
+            }
         }
     }
 }
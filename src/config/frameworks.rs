@@ -1,10 +1,55 @@
 use super::{toml, ConfigError};
 use std::collections::HashMap;
 use std::path::Path;
+use std::process::Command;
+
+/// The flags needed to build a unit test with a framework
+#[derive(Debug, PartialEq)]
+pub struct Framework {
+    compile_flags: Vec<String>,
+    link_flags: Vec<String>,
+}
+
+impl Framework {
+    fn build_from_toml(toml: toml::Framework) -> Result<Self, ConfigError> {
+        validate_legacy_flags(&toml.flags)?;
+        let mut compile_flags = toml.compile_flags;
+        for dir in toml.include_dirs.iter() {
+            let path = Path::new(dir);
+            if !path.is_dir() {
+                return Err(ConfigError::InvalidFrameworkDir(dir.clone()));
+            }
+            compile_flags.push(format!("-I{}", dir));
+        }
+        let mut link_flags = toml.flags;
+        link_flags.extend(toml.link_flags);
+        if let Some(package) = &toml.pkg_config {
+            compile_flags.extend(pkg_config(package, "--cflags")?);
+            link_flags.extend(pkg_config(package, "--libs")?);
+        }
+        Ok(Self {
+            compile_flags,
+            link_flags,
+        })
+    }
+
+    pub fn compile_flags(&self) -> &[String] {
+        &self.compile_flags
+    }
+
+    pub fn link_flags(&self) -> &[String] {
+        &self.link_flags
+    }
+
+    /// All flags for the framework, compile flags first
+    pub fn flags(&self) -> impl Iterator<Item = &String> {
+        self.compile_flags.iter().chain(self.link_flags.iter())
+    }
+}
 
 #[derive(Debug)]
 pub struct FrameworkManager {
-    frameworks: HashMap<String, Vec<String>>,
+    frameworks: HashMap<String, Framework>,
 }
 
 impl FrameworkManager {
@@ -13,7 +58,8 @@ impl FrameworkManager {
         if let Some(frameworks_struct) = toml {
             let vec = frameworks_struct.0;
             for framework in vec.into_iter() {
-                add_to_hashmap(framework, &mut frameworks)?;
+                let name = framework.name.clone();
+                frameworks.insert(name, Framework::build_from_toml(framework)?);
             }
             Ok(Self { frameworks })
         } else {
@@ -23,16 +69,25 @@ impl FrameworkManager {
         }
     }
 
-    pub fn get(&self, name: &str) -> Option<&Vec<String>> {
+    pub fn get(&self, name: &str) -> Option<&Framework> {
         self.frameworks.get(name)
     }
+
+    /// All frameworks, sorted by name
+    pub fn all(&self) -> Vec<(&str, &Framework)> {
+        let mut frameworks: Vec<(&str, &Framework)> = self
+            .frameworks
+            .iter()
+            .map(|(name, framework)| (name.as_str(), framework))
+            .collect();
+        frameworks.sort_by_key(|(name, _)| *name);
+        frameworks
+    }
 }
 
-fn add_to_hashmap(
-    toml: toml::Framework,
-    map: &mut HashMap<String, Vec<String>>,
-) -> Result<(), ConfigError> {
-    for val in toml.flags.iter() {
+/// `flags` only accepts libraries and library directories, which must exist
+fn validate_legacy_flags(flags: &[String]) -> Result<(), ConfigError> {
+    for val in flags.iter() {
         if !val.starts_with("-l") && !val.starts_with("-L") {
             return Err(ConfigError::InvalidFramework);
         }
@@ -43,6 +98,79 @@ fn add_to_hashmap(
             }
         }
     }
-    map.insert(toml.name, toml.flags);
     Ok(())
 }
+
+/// Ask `pkg-config` for the flags of `package`, where `query` is `--cflags` or `--libs`
+fn pkg_config(package: &str, query: &str) -> Result<Vec<String>, ConfigError> {
+    let output = Command::new("pkg-config")
+        .arg(query)
+        .arg(package)
+        .output()
+        .map_err(|e| ConfigError::PkgConfig(package.into(), e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ConfigError::PkgConfig(
+            package.into(),
+            stderr.trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .map(String::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framework_toml(name: &str) -> toml::Framework {
+        toml::Framework {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn compile_and_link_flags() -> Result<(), ConfigError> {
+        let framework = Framework::build_from_toml(toml::Framework {
+            flags: vec!["-lm".into()],
+            compile_flags: vec!["-std=c++17".into(), "-DTESTING".into()],
+            link_flags: vec!["-pthread".into()],
+            include_dirs: vec!["tst/resources".into()],
+            ..framework_toml("criterion")
+        })?;
+        assert_eq!(
+            framework.compile_flags(),
+            ["-std=c++17", "-DTESTING", "-Itst/resources"]
+        );
+        assert_eq!(framework.link_flags(), ["-lm", "-pthread"]);
+        assert_eq!(framework.flags().count(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_include_dir() {
+        let framework = Framework::build_from_toml(toml::Framework {
+            include_dirs: vec!["tst/resources/nonexistent".into()],
+            ..framework_toml("criterion")
+        });
+        assert!(matches!(
+            framework,
+            Err(ConfigError::InvalidFrameworkDir(dir)) if dir == "tst/resources/nonexistent"
+        ));
+    }
+
+    #[test]
+    fn missing_pkg_config_package() {
+        let framework = Framework::build_from_toml(toml::Framework {
+            pkg_config: Some("examtrainer-nonexistent-package".into()),
+            ..framework_toml("criterion")
+        });
+        assert!(matches!(
+            framework,
+            Err(ConfigError::PkgConfig(package, _)) if package == "examtrainer-nonexistent-package"
+        ));
+    }
+}
@@ -0,0 +1,47 @@
+use super::toml;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A named program that script submissions are run with, along with the arguments given to it
+/// before the script
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interpreter {
+    program: String,
+    args: Vec<String>,
+}
+
+impl Interpreter {
+    fn build_from_toml(toml: toml::Interpreter) -> Self {
+        Self {
+            program: toml.program,
+            args: toml.args,
+        }
+    }
+
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+}
+
+impl fmt::Display for Interpreter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in self.args.iter() {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn build_interpreters(
+    toml: Option<HashMap<String, toml::Interpreter>>,
+) -> HashMap<String, Interpreter> {
+    toml.unwrap_or_default()
+        .into_iter()
+        .map(|(name, interpreter)| (name, Interpreter::build_from_toml(interpreter)))
+        .collect()
+}
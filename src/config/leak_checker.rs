@@ -0,0 +1,67 @@
+use super::toml;
+use std::fmt;
+
+/// Exit code valgrind is told to use when it finds a leak, unlikely to be used by a submission
+const VALGRIND_EXIT_CODE: i32 = 93;
+
+/// A program that runs a binary and reports whether it leaked memory, such as valgrind. The
+/// binary and its arguments are given after `args`, and the checker exits with `exit_code` when
+/// it finds a leak.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeakChecker {
+    program: String,
+    args: Vec<String>,
+    exit_code: i32,
+}
+
+impl LeakChecker {
+    /// Build the [`LeakChecker`] from the `[leak_checker]` table of the config file, using
+    /// valgrind if there is none
+    pub fn build_from_toml(toml: Option<toml::LeakChecker>) -> Self {
+        match toml {
+            Some(toml) => Self {
+                program: toml.program,
+                args: toml.args,
+                exit_code: toml.exit_code,
+            },
+            None => Self::default(),
+        }
+    }
+
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code
+    }
+}
+
+impl Default for LeakChecker {
+    fn default() -> Self {
+        Self {
+            program: "valgrind".into(),
+            args: vec![
+                "-q".into(),
+                "--leak-check=full".into(),
+                "--errors-for-leak-kinds=definite,indirect".into(),
+                format!("--error-exitcode={}", VALGRIND_EXIT_CODE),
+            ],
+            exit_code: VALGRIND_EXIT_CODE,
+        }
+    }
+}
+
+impl fmt::Display for LeakChecker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in self.args.iter() {
+            write!(f, " {}", arg)?;
+        }
+        write!(f, " (exits with {} on a leak)", self.exit_code)
+    }
+}
@@ -2,8 +2,8 @@
 //
 //! A valid configuration file must contain the following:
 //! * `submit_directory` - The directory to submit exercises when sitting an exam
-//! * `question_directory` - Directory where questions are located
-//! * `exam_directory` - Directory where exams are located
+//! * `question_directory` - Directory where questions are located, or a list of directories
+//! * `exam_directory` - Directory where exams are located, or a list of directories
 //! * `subject_directory` - Directory where the subjects of assignments will be sent
 //!
 //! Below is a valid config file:
@@ -14,145 +14,427 @@
 //! exam_directory = "/home/rlucas/.config/examtrainer/exams"
 //! subject_directory = "/home/rlucas/subjects"
 //! ```
+//!
+//! When a list of question or exam directories is given, they are searched in order, and a
+//! question or exam found in an earlier directory takes precedence over one with the same name
+//! in a later directory.
+//!
+//! Directories may start with `~`, and may contain environment variables (`$VAR` or `${VAR}`).
+//! Relative directories are resolved against the directory containing the config file, and all
+//! directories are stored as canonical, absolute paths.
+//!
+//! The config file is looked for in the following order:
+//! 1. The path given to [`Config::new_from`] (the `--config` command line flag)
+//! 2. The path in the `EXAMTRAINER_CONFIG` environment variable
+//! 3. `$XDG_CONFIG_HOME/examtrainer/config.toml`, if `XDG_CONFIG_HOME` is set
+//! 4. `$HOME/.config/examtrainer/config.toml`
+//!
+//! Toolchains can be defined in the config file, and used by questions in place of a `compiler`
+//! (with `toolchain = "c"`), so that every question using them can be switched to a different
+//! compiler or set of flags at once:
+//! ```toml
+//! [toolchains.c]
+//! compiler = "gcc"
+//! standard = "c99"
+//! flags = ["-Wall", "-Wextra", "-Werror"]
+//! ```
+//!
+//! Interpreters can be defined in the same way, to run `script` submissions with
+//! (`interpreter = "python"`). Any `args` are given to the program before the script:
+//! ```toml
+//! [interpreters.python]
+//! program = "python3"
+//! args = ["-B"]
+//! ```
+//!
+//! Questions with `leak_check = true` rerun the submission under valgrind. Any other program
+//! that runs a binary given after its `args`, and exits with `exit_code` when the binary leaks,
+//! can be used instead:
+//! ```toml
+//! [leak_checker]
+//! program = "valgrind"
+//! args = ["-q", "--leak-check=full", "--error-exitcode=93"]
+//! exit_code = 93
+//! ```
+//!
+//! The `[grading]` table is optional, and sets how many seconds each run of a binary may take
+//! before it is killed (10 by default). Questions can override this for themselves, or for each
+//! set of arguments. It can also turn on `sanitize` for every question that doesn't set it:
+//! ```toml
+//! [grading]
+//! timeout = 5
+//! sanitize = true
+//! ```
+//!
+//! If the config file, or any question or exam directory, does not exist, a [`Prompter`] is
+//! asked whether it should be created. [`Config::new`] and [`Config::new_from`] ask on the
+//! terminal, while [`Config::with_prompter`] accepts any [`Prompter`], such as [`FixedAnswer`].
+//!
+//! Each directory can also be overridden with an environment variable, which takes precedence
+//! over the config file: `EXAMTRAINER_SUBMIT_DIR`, `EXAMTRAINER_QUESTION_DIR`,
+//! `EXAMTRAINER_EXAM_DIR`, `EXAMTRAINER_SUBJECT_DIR` and `EXAMTRAINER_TRACE_DIR`.
 
 pub mod error;
 mod frameworks;
+mod interpreters;
+mod leak_checker;
+mod paths;
+pub mod prompt;
 mod toml;
+mod toolchains;
 
 pub use error::ConfigError;
+pub use frameworks::Framework;
+pub use interpreters::Interpreter;
+pub use leak_checker::LeakChecker;
+pub use prompt::{FixedAnswer, Prompter, StdinPrompter};
+pub use toolchains::Toolchain;
 
 use frameworks::FrameworkManager;
+use std::collections::HashMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long each run of a binary may take, if no timeout is given in the config file
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug)]
 struct Directories {
-    submit_directory: String,
-    question_directory: String,
-    exam_directory: String,
-    subject_directory: String,
-    trace_directory: String,
+    submit_directory: PathBuf,
+    question_directories: Vec<PathBuf>,
+    exam_directories: Vec<PathBuf>,
+    subject_directory: PathBuf,
+    trace_directory: PathBuf,
 }
 
 impl fmt::Display for Directories {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "Submit directory: {}", self.submit_directory)?;
-        writeln!(f, "Question directory: {}", self.question_directory)?;
-        writeln!(f, "Exam directory: {}", self.exam_directory)?;
-        writeln!(f, "Subject directory: {}", self.subject_directory)?;
-        writeln!(f, "Trace directory: {}", self.trace_directory)
+        writeln!(f, "Submit directory: {}", self.submit_directory.display())?;
+        for question_directory in self.question_directories.iter() {
+            writeln!(f, "Question directory: {}", question_directory.display())?;
+        }
+        for exam_directory in self.exam_directories.iter() {
+            writeln!(f, "Exam directory: {}", exam_directory.display())?;
+        }
+        writeln!(f, "Subject directory: {}", self.subject_directory.display())?;
+        writeln!(f, "Trace directory: {}", self.trace_directory.display())
     }
 }
 
 impl From<toml::Directories> for Directories {
     fn from(input: toml::Directories) -> Self {
         Self {
-            submit_directory: input.submit_directory,
-            question_directory: input.question_directory,
-            exam_directory: input.exam_directory,
-            subject_directory: input.subject_directory,
-            trace_directory: input.trace_directory,
+            submit_directory: input.submit_directory.into(),
+            question_directories: input
+                .question_directory
+                .into_iter()
+                .map(PathBuf::from)
+                .collect(),
+            exam_directories: input
+                .exam_directory
+                .into_iter()
+                .map(PathBuf::from)
+                .collect(),
+            subject_directory: input.subject_directory.into(),
+            trace_directory: input.trace_directory.into(),
         }
     }
 }
 
+// Directories are built from validated UTF-8 strings when the config file is read, so this will
+// not panic.
+fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
 #[derive(Debug)]
 pub struct Config {
     directories: Directories,
     frameworks: FrameworkManager,
+    toolchains: HashMap<String, Toolchain>,
+    interpreters: HashMap<String, Interpreter>,
+    leak_checker: LeakChecker,
+    timeout: Duration,
+    sanitize: bool,
 }
 
 impl Config {
     /// Parse config file to create a [`Config`] for `examtrainer`
     ///
-    /// [`Config::new`] will search for a config.toml file inside of `$HOME/.config/examtrainer/`.
-    /// If it is not found, or either of the two required directories within (exams and questions)
-    /// are not found, the user will be prompted to create them.
+    /// [`Config::new`] will use the config file named by `$EXAMTRAINER_CONFIG`, or otherwise
+    /// search for a config.toml file inside of `$XDG_CONFIG_HOME/examtrainer/` (falling back to
+    /// `$HOME/.config/examtrainer/`). If it is not found, or either of the two required
+    /// directories within (exams and questions) are not found, the user will be prompted to
+    /// create them.
     ///
     /// This function returns either `Ok([`Config`])`, or `Err([`ConfigError`])`.
     pub fn new() -> Result<Self, ConfigError> {
-        let config_toml = toml::Config::new()?;
-        Self::new_internal(config_toml)
+        Self::with_prompter(None, &mut StdinPrompter)
     }
 
+    /// Parse the config file at `config_path`, prompting the user as in [`Config::new`]
     pub fn new_from(config_path: &str) -> Result<Self, ConfigError> {
-        let config_toml = toml::Config::new_from(config_path)?;
-        Self::new_internal(config_toml)
+        Self::with_prompter(Some(config_path), &mut StdinPrompter)
     }
 
-    fn new_internal(config_toml: toml::Config) -> Result<Self, ConfigError> {
+    /// Parse the config file at `config_path` (or the default config file if it is [`None`]),
+    /// using `prompter` to decide whether anything missing should be created.
+    pub fn with_prompter(
+        config_path: Option<&str>,
+        prompter: &mut dyn Prompter,
+    ) -> Result<Self, ConfigError> {
+        Self::load(config_path, false, prompter)
+    }
+
+    /// Like [`Config::with_prompter`], but for `init`: a config file named by `config_path` or
+    /// `$EXAMTRAINER_CONFIG` that does not exist yet is created, instead of being an error.
+    pub fn for_init(
+        config_path: Option<&str>,
+        prompter: &mut dyn Prompter,
+    ) -> Result<Self, ConfigError> {
+        Self::load(config_path, true, prompter)
+    }
+
+    fn load(
+        config_path: Option<&str>,
+        init: bool,
+        prompter: &mut dyn Prompter,
+    ) -> Result<Self, ConfigError> {
+        let config_toml = toml::Config::new(config_path, init, prompter)?;
         let directories = config_toml.directories.into();
         let frameworks = FrameworkManager::new(config_toml.frameworks)?;
+        let toolchains = toolchains::build_toolchains(config_toml.toolchains);
+        let interpreters = interpreters::build_interpreters(config_toml.interpreters);
+        let leak_checker = LeakChecker::build_from_toml(config_toml.leak_checker);
+        let grading = config_toml.grading;
+        let sanitize = grading
+            .as_ref()
+            .and_then(|grading| grading.sanitize)
+            .unwrap_or(false);
+        let timeout = match grading.and_then(|grading| grading.timeout) {
+            Some(seconds) => crate::utils::duration_from_secs(seconds)
+                .ok_or(ConfigError::InvalidTimeout(seconds))?,
+            None => DEFAULT_TIMEOUT,
+        };
         Ok(Self {
             directories,
             frameworks,
+            toolchains,
+            interpreters,
+            leak_checker,
+            timeout,
+            sanitize,
         })
     }
 
     pub fn submit_dir(&self) -> &str {
-        &self.directories.submit_directory
+        path_str(&self.directories.submit_directory)
     }
-    pub fn question_dir(&self) -> &str {
-        &self.directories.question_directory
+    /// Question directories, in order of precedence
+    pub fn question_dirs(&self) -> Vec<&str> {
+        self.directories
+            .question_directories
+            .iter()
+            .map(|dir| path_str(dir))
+            .collect()
     }
-    pub fn exam_dir(&self) -> &str {
-        &self.directories.exam_directory
+    /// Exam directories, in order of precedence
+    pub fn exam_dirs(&self) -> Vec<&str> {
+        self.directories
+            .exam_directories
+            .iter()
+            .map(|dir| path_str(dir))
+            .collect()
     }
     pub fn subject_dir(&self) -> &str {
-        &self.directories.subject_directory
+        path_str(&self.directories.subject_directory)
     }
     pub fn trace_dir(&self) -> &str {
-        &self.directories.trace_directory
+        path_str(&self.directories.trace_directory)
     }
-    pub fn get_framework(&self, name: &str) -> Option<&Vec<String>> {
+    pub fn get_framework(&self, name: &str) -> Option<&Framework> {
         self.frameworks.get(name)
     }
+    /// All configured frameworks, sorted by name
+    pub fn frameworks(&self) -> Vec<(&str, &Framework)> {
+        self.frameworks.all()
+    }
+    pub fn get_toolchain(&self, name: &str) -> Option<&Toolchain> {
+        self.toolchains.get(name)
+    }
+    pub fn get_interpreter(&self, name: &str) -> Option<&Interpreter> {
+        self.interpreters.get(name)
+    }
+    /// All configured interpreters, sorted by name
+    pub fn interpreters(&self) -> Vec<(&str, &Interpreter)> {
+        let mut interpreters: Vec<_> = self
+            .interpreters
+            .iter()
+            .map(|(name, interpreter)| (&name[..], interpreter))
+            .collect();
+        interpreters.sort_by_key(|(name, _)| *name);
+        interpreters
+    }
+    /// The program submissions are rerun with, when a question checks for memory leaks
+    pub fn leak_checker(&self) -> &LeakChecker {
+        &self.leak_checker
+    }
+    /// How long each run of a binary may take, unless a question gives its own timeout
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+    /// Whether passing submissions are rebuilt with sanitizers, unless a question says otherwise
+    pub fn sanitize(&self) -> bool {
+        self.sanitize
+    }
 }
 
 impl fmt::Display for Config {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.directories)
+        write!(f, "{}", self.directories)?;
+        let mut toolchains: Vec<_> = self.toolchains.iter().collect();
+        toolchains.sort_by_key(|(name, _)| *name);
+        for (name, toolchain) in toolchains {
+            writeln!(f, "Toolchain {}: {}", name, toolchain)?;
+        }
+        for (name, interpreter) in self.interpreters() {
+            writeln!(f, "Interpreter {}: {}", name, interpreter)?;
+        }
+        writeln!(f, "Leak checker: {}", self.leak_checker)?;
+        Ok(())
     }
 }
 
-// Config is difficult to test, as parts are interactive (asking the user if they want to create
-// directories, etc.)
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    /// Answers every question, remembering the paths it was asked about
+    struct Recorder {
+        answer: bool,
+        asked: Vec<String>,
+    }
+
+    impl Prompter for Recorder {
+        fn create_directory(&mut self, path: &str) -> Result<bool, ConfigError> {
+            self.asked.push(path.to_string());
+            Ok(self.answer)
+        }
+
+        fn create_default_config(&mut self, path: &str) -> Result<bool, ConfigError> {
+            self.asked.push(path.to_string());
+            Ok(self.answer)
+        }
+    }
+
+    fn write_temp_config(name: &str) -> Result<PathBuf, ConfigError> {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir)?;
+        let config_file = dir.join("config.toml");
+        std::fs::write(
+            &config_file,
+            "[directories]
+submit_directory = \"rendu\"
+question_directory = \"questions\"
+exam_directory = \"exams\"
+subject_directory = \"subjects\"
+trace_directory = \"trace\"
+",
+        )?;
+        Ok(config_file)
+    }
+
     #[test]
-    fn initialize_config() -> Result<(), ConfigError> {
-        let config = Config::new_from("tst/resources/config_1.toml")?;
-        assert_eq!(config.directories.submit_directory, "/home/rlucas/rendu");
+    fn prompter_declines() -> Result<(), ConfigError> {
+        let config_file = write_temp_config("examtrainer-prompter-declines")?;
+        let mut recorder = Recorder {
+            answer: false,
+            asked: Vec::new(),
+        };
+        let config_res = Config::with_prompter(config_file.to_str(), &mut recorder);
+        assert!(matches!(
+            config_res,
+            Err(ConfigError::NoQuestionDirectory(_))
+        ));
+        assert_eq!(recorder.asked.len(), 1);
+        assert!(recorder.asked[0].ends_with("questions"));
+        assert!(!config_file.with_file_name("questions").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn prompter_accepts() -> Result<(), ConfigError> {
+        let config_file = write_temp_config("examtrainer-prompter-accepts")?;
+        let config = Config::with_prompter(config_file.to_str(), &mut FixedAnswer(true))?;
+        assert!(Path::new(config.question_dirs()[0]).is_dir());
+        assert!(Path::new(config.exam_dirs()[0]).is_dir());
+        std::fs::remove_dir_all(config_file.parent().unwrap())?;
+        Ok(())
+    }
+
+    #[test]
+    fn missing_config_file() {
+        let config_file = std::env::temp_dir().join("examtrainer-missing-config/config.toml");
+        let config_res = Config::with_prompter(config_file.to_str(), &mut FixedAnswer(true));
+        assert!(matches!(config_res, Err(ConfigError::NoConfigFile(_))));
+        assert!(!config_file.exists());
+    }
+
+    #[test]
+    fn init_creates_config_file() -> Result<(), ConfigError> {
+        let dir = std::env::temp_dir().join("examtrainer-init-config");
+        let _ = std::fs::remove_dir_all(&dir);
+        let config_file = dir.join("new.toml");
+        let config = Config::for_init(config_file.to_str(), &mut FixedAnswer(true))?;
+        assert!(config_file.is_file());
         assert_eq!(
-            config.directories.question_directory,
-            "/home/rlucas/.config/examtrainer/questions"
+            config.question_dirs(),
+            [dir.join("questions").to_str().unwrap()]
         );
+        assert!(dir.join("questions").is_dir());
+        assert!(dir.join("exams").is_dir());
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn initialize_config() -> Result<(), ConfigError> {
+        let config = Config::new_from("tst/resources/config_1.toml")?;
+        assert_eq!(config.submit_dir(), "/home/rlucas/rendu");
         assert_eq!(
-            config.directories.exam_directory,
-            "/home/rlucas/.config/examtrainer/exams"
+            config.question_dirs(),
+            vec!["/home/rlucas/.config/examtrainer/questions"]
         );
         assert_eq!(
-            config.directories.subject_directory,
-            "/home/rlucas/subjects"
+            config.exam_dirs(),
+            vec!["/home/rlucas/.config/examtrainer/exams"]
         );
+        assert_eq!(config.subject_dir(), "/home/rlucas/subjects");
+        assert_eq!(config.timeout(), DEFAULT_TIMEOUT);
         Ok(())
     }
 
     #[test]
     fn initialize_test_config() -> Result<(), ConfigError> {
         let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let resources = Path::new("tst/resources").canonicalize()?;
         assert_eq!(
             config.directories.submit_directory,
-            "tst/resources/rendu_test"
+            resources.join("rendu_test")
         );
         assert_eq!(
-            config.directories.question_directory,
-            "tst/resources/questions"
+            config.directories.question_directories,
+            vec![resources.join("questions")]
+        );
+        assert_eq!(
+            config.directories.exam_directories,
+            vec![resources.join("exams")]
         );
-        assert_eq!(config.directories.exam_directory, "tst/resources/exams");
         assert_eq!(
             config.directories.subject_directory,
-            "tst/resources/subjects"
+            resources.join("subjects")
         );
         Ok(())
     }
@@ -165,8 +447,8 @@ mod tests {
         assert!(gtest_flags.is_some());
         let gtest_flags = gtest_flags.unwrap();
         assert_eq!(
-            *gtest_flags,
-            vec![
+            gtest_flags.link_flags(),
+            [
                 "-lgtest".to_string(),
                 "-lpthread".to_string(),
                 "-L/mnt/hard_drive/usr/lib/googletest/build/lib".to_string()
@@ -175,6 +457,72 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn framework_compile_flags() -> Result<(), ConfigError> {
+        let config = Config::new_from("tst/resources/framework_config.toml")?;
+        let questions = Path::new("tst/resources/questions").canonicalize()?;
+        let criterion = config.get_framework("criterion");
+        assert!(criterion.is_some());
+        let criterion = criterion.unwrap();
+        assert_eq!(
+            criterion.compile_flags(),
+            [
+                "-std=c11".to_string(),
+                "-DTESTING".to_string(),
+                format!("-I{}", questions.display())
+            ]
+        );
+        assert_eq!(criterion.link_flags(), ["-pthread"]);
+        let math = config.get_framework("math").unwrap();
+        assert!(math.compile_flags().is_empty());
+        assert_eq!(math.link_flags(), ["-lm"]);
+        Ok(())
+    }
+
+    #[test]
+    fn toolchains() -> Result<(), ConfigError> {
+        let config = Config::new_from("tst/resources/toolchain_config.toml")?;
+        let c = config.get_toolchain("c");
+        assert!(c.is_some());
+        let c = c.unwrap();
+        assert_eq!(c.compiler(), "gcc");
+        assert_eq!(c.flags(), ["-std=c99", "-Wall", "-Wextra", "-Werror"]);
+        assert_eq!(config.get_toolchain("cpp").unwrap().compiler(), "g++");
+        assert!(config.get_toolchain("rust").is_none());
+        assert_eq!(config.timeout(), Duration::from_millis(2500));
+        Ok(())
+    }
+
+    #[test]
+    fn interpreters() -> Result<(), ConfigError> {
+        let config = Config::new_from("tst/resources/script_config.toml")?;
+        let python = config.get_interpreter("python").unwrap();
+        assert_eq!(python.program(), "python3");
+        assert_eq!(python.args(), ["-B"]);
+        assert_eq!(python.to_string(), "python3 -B");
+        assert!(config.get_interpreter("shell").unwrap().args().is_empty());
+        let names: Vec<&str> = config
+            .interpreters()
+            .iter()
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(names, ["python", "shell"]);
+        Ok(())
+    }
+
+    #[test]
+    fn leak_checkers() -> Result<(), ConfigError> {
+        let config = Config::new_from("tst/resources/script_config.toml")?;
+        assert_eq!(config.leak_checker(), &LeakChecker::default());
+        assert_eq!(config.leak_checker().program(), "valgrind");
+        let config = Config::new_from("tst/resources/leak_config.toml")?;
+        let checker = config.leak_checker();
+        assert_eq!(checker.program(), "sh");
+        assert_eq!(checker.args().len(), 3);
+        assert_eq!(checker.exit_code(), 23);
+        Ok(())
+    }
+
     #[test]
     #[ignore]
     fn invalid_framework_test() -> Result<(), ConfigError> {
@@ -0,0 +1,173 @@
+//! Expansion and resolution of the directories given in a config file
+//!
+//! Directories may start with `~`, contain environment variables written as `$VAR` or `${VAR}`,
+//! and may be relative. Relative directories are resolved against a base directory (normally the
+//! directory containing the config file), and the result is canonicalized as far as it exists on
+//! disk, so that directories which will be created later are still given an absolute path.
+
+use super::ConfigError;
+use home::home_dir;
+use std::path::{Component, Path, PathBuf};
+
+/// Expand `path` and resolve it against `base` if it is relative.
+///
+/// Environment variables are looked up with `lookup`, and an [`ConfigError::UndefinedVariable`]
+/// is returned if one of them is not set.
+pub fn resolve<F>(path: &str, base: &Path, lookup: F) -> Result<PathBuf, ConfigError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let expanded = expand(path, lookup)?;
+    let expanded = Path::new(&expanded);
+    let absolute = if expanded.is_absolute() {
+        expanded.to_path_buf()
+    } else {
+        base.join(expanded)
+    };
+    Ok(canonicalize(&normalize(&absolute)))
+}
+
+fn expand<F>(path: &str, lookup: F) -> Result<String, ConfigError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::new();
+    let rest = if path == "~" || path.starts_with("~/") {
+        let home = home_dir().ok_or(ConfigError::NoHomeDirectory)?;
+        output.push_str(&home.to_string_lossy());
+        &path[1..]
+    } else {
+        path
+    };
+
+    let mut chars = rest.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            output.push(c);
+            continue;
+        }
+        let mut name = String::new();
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('}') => break,
+                    Some(c) => name.push(c),
+                    None => return Err(ConfigError::InvalidPath(path.into())),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if !c.is_ascii_alphanumeric() && c != '_' {
+                    break;
+                }
+                name.push(c);
+                chars.next();
+            }
+            if name.is_empty() {
+                output.push('$');
+                continue;
+            }
+        }
+        let value = lookup(&name).ok_or(ConfigError::UndefinedVariable(name))?;
+        output.push_str(&value);
+    }
+    Ok(output)
+}
+
+/// Remove `.` and `..` components without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normal.pop();
+            }
+            other => normal.push(other),
+        }
+    }
+    normal
+}
+
+/// Canonicalize the longest part of `path` that exists, and append the remainder
+fn canonicalize(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut remainder = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return remainder
+                .iter()
+                .rev()
+                .fold(canonical, |acc, name| acc.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                remainder.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(var: &str) -> Option<String> {
+        match var {
+            "RENDU" => Some("/tmp/rendu".to_string()),
+            "USER" => Some("rlucas".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expand_variables() -> Result<(), ConfigError> {
+        assert_eq!(expand("$RENDU/ex00", lookup)?, "/tmp/rendu/ex00");
+        assert_eq!(
+            expand("/home/${USER}_2/rendu", lookup)?,
+            "/home/rlucas_2/rendu"
+        );
+        assert_eq!(expand("/tmp/$/cost$", lookup)?, "/tmp/$/cost$");
+        assert!(matches!(
+            expand("$UNDEFINED/rendu", lookup),
+            Err(ConfigError::UndefinedVariable(var)) if var == "UNDEFINED"
+        ));
+        assert!(matches!(
+            expand("${USER/rendu", lookup),
+            Err(ConfigError::InvalidPath(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn expand_home() -> Result<(), ConfigError> {
+        let home = home_dir().ok_or(ConfigError::NoHomeDirectory)?;
+        assert_eq!(
+            expand("~/rendu", lookup)?,
+            format!("{}/rendu", home.display())
+        );
+        assert_eq!(expand("/tmp/~", lookup)?, "/tmp/~");
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_relative() -> Result<(), ConfigError> {
+        let base = Path::new("tst/resources").canonicalize()?;
+        assert_eq!(
+            resolve("questions", &base, lookup)?,
+            Path::new("tst/resources/questions").canonicalize()?
+        );
+        assert_eq!(
+            resolve("./subjects/../not_created_yet", &base, lookup)?,
+            base.join("not_created_yet")
+        );
+        assert_eq!(
+            resolve("/not_a_dir/./rendu", &base, lookup)?,
+            PathBuf::from("/not_a_dir/rendu")
+        );
+        Ok(())
+    }
+}
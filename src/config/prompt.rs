@@ -0,0 +1,62 @@
+//! Questions asked while reading the config file
+//!
+//! When the config file or one of its directories is missing, [`Config`](super::Config) asks
+//! whether it should be created. The answers come from a [`Prompter`], so that they can be read
+//! from the terminal ([`StdinPrompter`]), fixed in advance ([`FixedAnswer`], used by the `--yes`
+//! and `--no` flags), or supplied by a library user.
+
+use super::ConfigError;
+use std::io;
+
+/// Answers the questions asked while reading the config file
+pub trait Prompter {
+    /// Should the missing directory `path` be created?
+    fn create_directory(&mut self, path: &str) -> Result<bool, ConfigError>;
+
+    /// Should a default config file be created at the missing `path`?
+    fn create_default_config(&mut self, path: &str) -> Result<bool, ConfigError>;
+}
+
+/// Asks the user each question on the terminal, reading a y/n answer from stdin
+#[derive(Debug, Default)]
+pub struct StdinPrompter;
+
+impl StdinPrompter {
+    fn ask_yes_or_no(warning: &str, question: &str) -> Result<bool, ConfigError> {
+        let mut buffer = String::new();
+        println!("    Warning: {}", warning);
+        println!("{} [y/n]: ", question);
+        io::stdin().read_line(&mut buffer)?;
+        Ok(buffer.trim().to_lowercase() == "y")
+    }
+}
+
+impl Prompter for StdinPrompter {
+    fn create_directory(&mut self, path: &str) -> Result<bool, ConfigError> {
+        Self::ask_yes_or_no(
+            &format!("Directory {} does not exist", path),
+            "Create this directory?",
+        )
+    }
+
+    fn create_default_config(&mut self, path: &str) -> Result<bool, ConfigError> {
+        Self::ask_yes_or_no(
+            &format!("Config file {} does not exist", path),
+            "Create default configuration file?",
+        )
+    }
+}
+
+/// Gives the same answer to every question, without any interaction
+#[derive(Debug)]
+pub struct FixedAnswer(pub bool);
+
+impl Prompter for FixedAnswer {
+    fn create_directory(&mut self, _path: &str) -> Result<bool, ConfigError> {
+        Ok(self.0)
+    }
+
+    fn create_default_config(&mut self, _path: &str) -> Result<bool, ConfigError> {
+        Ok(self.0)
+    }
+}
@@ -1,137 +1,364 @@
-use super::ConfigError;
+use super::{paths, ConfigError, Prompter};
 use home::home_dir;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the config file to use instead of the default location
+pub const CONFIG_VAR: &str = "EXAMTRAINER_CONFIG";
 
 macro_rules! check_if_dir_exists {
-    ($path:ident, $error:expr) => {
+    ($path:ident, $error:expr, $prompter:expr) => {
         match Path::new(&$path).exists() {
             true => (),
-            false => ask_to_create_directory(&$path, $error)?,
+            false => ask_to_create_directory(&$path, $error, $prompter)?,
         }
     };
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Debug, Default, PartialEq)]
 pub struct Framework {
     pub name: String,
+    /// Libraries and library directories, which may only be `-l` or `-L` flags
+    #[serde(default)]
     pub flags: Vec<String>,
+    #[serde(default)]
+    pub compile_flags: Vec<String>,
+    #[serde(default)]
+    pub link_flags: Vec<String>,
+    #[serde(default)]
+    pub include_dirs: Vec<String>,
+    pub pkg_config: Option<String>,
+}
+
+impl Framework {
+    /// Expand and resolve `include_dirs` against `config_dir`, as for [`Directories`]
+    fn resolve<F>(&mut self, config_dir: &Path, lookup: F) -> Result<(), ConfigError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        self.include_dirs = self
+            .include_dirs
+            .iter()
+            .map(|dir| resolve_to_string(dir, config_dir, &lookup))
+            .collect::<Result<_, _>>()?;
+        Ok(())
+    }
 }
 
 #[derive(Deserialize, Debug)]
 pub struct Frameworks(pub Vec<Framework>);
 
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Toolchain {
+    pub compiler: String,
+    #[serde(default)]
+    pub flags: Vec<String>,
+    pub standard: Option<String>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Interpreter {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct LeakChecker {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Exit code the checker uses to report a leak
+    pub exit_code: i32,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Directories {
     pub submit_directory: String,
-    pub question_directory: String,
-    pub exam_directory: String,
+    #[serde(deserialize_with = "one_or_many")]
+    pub question_directory: Vec<String>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub exam_directory: Vec<String>,
     pub subject_directory: String,
     pub trace_directory: String,
 }
 
+/// Accept either a single directory, or a list of directories
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(directory) => Ok(vec![directory]),
+        OneOrMany::Many(directories) => Ok(directories),
+    }
+}
+
+fn resolve_to_string<F>(path: &str, base: &Path, lookup: F) -> Result<String, ConfigError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let resolved = paths::resolve(path, base, lookup)?;
+    resolved
+        .to_str()
+        .map(String::from)
+        .ok_or_else(|| ConfigError::InvalidPath(resolved.display().to_string()))
+}
+
+impl Directories {
+    /// Expand and resolve every directory, replacing any directory that has a corresponding
+    /// environment variable set, as returned by `lookup`:
+    /// * `EXAMTRAINER_SUBMIT_DIR`
+    /// * `EXAMTRAINER_QUESTION_DIR`
+    /// * `EXAMTRAINER_EXAM_DIR`
+    /// * `EXAMTRAINER_SUBJECT_DIR`
+    /// * `EXAMTRAINER_TRACE_DIR`
+    ///
+    /// `EXAMTRAINER_QUESTION_DIR` and `EXAMTRAINER_EXAM_DIR` may hold several directories,
+    /// separated in the same way as `$PATH`.
+    ///
+    /// Relative directories from the config file are resolved against `config_dir`, while
+    /// relative directories from the environment are resolved against `current_dir`.
+    fn resolve<F>(
+        &mut self,
+        config_dir: &Path,
+        current_dir: &Path,
+        lookup: F,
+    ) -> Result<(), ConfigError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let overrides = [
+            ("EXAMTRAINER_SUBMIT_DIR", &mut self.submit_directory),
+            ("EXAMTRAINER_SUBJECT_DIR", &mut self.subject_directory),
+            ("EXAMTRAINER_TRACE_DIR", &mut self.trace_directory),
+        ];
+        for (var, directory) in overrides {
+            *directory = match lookup(var) {
+                Some(value) => resolve_to_string(&value, current_dir, &lookup)?,
+                None => resolve_to_string(directory, config_dir, &lookup)?,
+            };
+        }
+        let list_overrides = [
+            ("EXAMTRAINER_QUESTION_DIR", &mut self.question_directory),
+            ("EXAMTRAINER_EXAM_DIR", &mut self.exam_directory),
+        ];
+        for (var, directories) in list_overrides {
+            *directories = match lookup(var) {
+                Some(value) => std::env::split_paths(&value)
+                    .map(|path| resolve_to_string(&path.to_string_lossy(), current_dir, &lookup))
+                    .collect::<Result<_, _>>()?,
+                None => directories
+                    .iter()
+                    .map(|directory| resolve_to_string(directory, config_dir, &lookup))
+                    .collect::<Result<_, _>>()?,
+            };
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Grading {
+    /// Default number of seconds each run of a binary may take
+    pub timeout: Option<f64>,
+    /// Rebuild passing submissions with sanitizers, unless a question says otherwise
+    pub sanitize: Option<bool>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Config {
     pub directories: Directories,
+    pub grading: Option<Grading>,
     pub frameworks: Option<Frameworks>,
+    pub toolchains: Option<HashMap<String, Toolchain>>,
+    pub interpreters: Option<HashMap<String, Interpreter>>,
+    pub leak_checker: Option<LeakChecker>,
 }
 
 impl Config {
-    pub fn new() -> Result<Self, ConfigError> {
-        Self::new_internal(None)
-    }
-
-    /// Parse config file given as argument to create a [`Config`]
-    pub fn new_from(config_path: &str) -> Result<Self, ConfigError> {
-        Self::new_internal(Some(config_path))
-    }
-
-    fn new_internal(config_path: Option<&str>) -> Result<Self, ConfigError> {
+    /// Parse the config file at `config_path`, or the default config file if it is [`None`],
+    /// asking `prompter` whether anything missing should be created
+    pub fn new(
+        config_path: Option<&str>,
+        init: bool,
+        prompter: &mut dyn Prompter,
+    ) -> Result<Self, ConfigError> {
         let mut buffer = String::new();
-        let mut config = open_config_file(config_path)?;
+        let (mut config, config_file) = open_config_file(config_path, init, prompter)?;
         config.read_to_string(&mut buffer)?;
-        let config: Config = toml_parse::from_str(&buffer)?;
-        let question_dir = &config.directories.question_directory;
-        let exam_dir = &config.directories.exam_directory;
-        check_if_dir_exists!(
-            question_dir,
-            ConfigError::NoQuestionDirectory(question_dir.clone())
-        );
-        check_if_dir_exists!(exam_dir, ConfigError::NoExamDirectory(exam_dir.clone()));
+        let mut config: Config = toml_parse::from_str(&buffer)?;
+        let current_dir = std::env::current_dir()?;
+        let config_dir = match config_file.parent() {
+            Some(parent) => current_dir.join(parent),
+            None => current_dir.clone(),
+        };
+        config
+            .directories
+            .resolve(&config_dir, &current_dir, env_var)?;
+        if let Some(frameworks) = &mut config.frameworks {
+            for framework in frameworks.0.iter_mut() {
+                framework.resolve(&config_dir, env_var)?;
+            }
+        }
+        for question_dir in config.directories.question_directory.iter() {
+            check_if_dir_exists!(
+                question_dir,
+                ConfigError::NoQuestionDirectory(question_dir.clone()),
+                prompter
+            );
+        }
+        for exam_dir in config.directories.exam_directory.iter() {
+            check_if_dir_exists!(
+                exam_dir,
+                ConfigError::NoExamDirectory(exam_dir.clone()),
+                prompter
+            );
+        }
         Ok(config)
     }
 }
 
-fn open_config_file(config_path: Option<&str>) -> Result<File, ConfigError> {
-    if let Some(config_path) = config_path {
-        File::open(&config_path).map_err(|e| e.into())
+/// Read an environment variable, treating an empty value the same as an unset one
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Find the base configuration directory: `$XDG_CONFIG_HOME` if it is set, otherwise
+/// `$HOME/.config`.
+fn config_home<F>(home: &Path, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    lookup("XDG_CONFIG_HOME").unwrap_or_else(|| format!("{}/{}", home.display(), ".config"))
+}
+
+/// Open the config file at `config_path`, or the one named by `$EXAMTRAINER_CONFIG`, or the
+/// default one. An explicitly named config file that does not exist is only created by `init`.
+fn open_config_file(
+    config_path: Option<&str>,
+    init: bool,
+    prompter: &mut dyn Prompter,
+) -> Result<(File, PathBuf), ConfigError> {
+    if let Some(config_path) = config_path
+        .map(String::from)
+        .or_else(|| env_var(CONFIG_VAR))
+    {
+        let file = match File::open(&config_path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                if init {
+                    create_config_at(&config_path)?
+                } else {
+                    return Err(ConfigError::NoConfigFile(config_path));
+                }
+            }
+            file => file?,
+        };
+        Ok((file, config_path.into()))
     } else {
         let home = home_dir().ok_or(ConfigError::NoHomeDirectory)?;
-        let config_dir = format!("{}/{}", home.display(), ".config");
+        let config_dir = config_home(&home, env_var);
         let examtrainer_dir = format!("{}/{}", config_dir, "examtrainer");
         let config_file = format!("{}/{}", examtrainer_dir, "config.toml");
 
-        check_if_dir_exists!(config_dir, ConfigError::NoConfigDirectory);
-        check_if_dir_exists!(examtrainer_dir, ConfigError::NoExamTrainerDirectory);
-        File::open(&config_file).or_else(|error| {
+        check_if_dir_exists!(config_dir, ConfigError::NoConfigDirectory, prompter);
+        check_if_dir_exists!(
+            examtrainer_dir,
+            ConfigError::NoExamTrainerDirectory,
+            prompter
+        );
+        let file = File::open(&config_file).or_else(|error| {
             if error.kind() == io::ErrorKind::NotFound {
-                ask_to_create_default_config(home.to_str().unwrap(), &config_file)
+                ask_to_create_default_config(
+                    home.to_str().unwrap(),
+                    &examtrainer_dir,
+                    &config_file,
+                    prompter,
+                )
             } else {
                 Err(error.into())
             }
-        })
+        })?;
+        Ok((file, config_file.into()))
     }
 }
 
 fn create_directory(path: &str) -> Result<(), ConfigError> {
     println!("Creating directory {}...", path);
-    std::fs::create_dir(path)?;
+    std::fs::create_dir_all(path)?;
     println!("Success!");
     Ok(())
 }
 
-fn ask_to_create_directory(path: &str, error: ConfigError) -> Result<(), ConfigError> {
-    let mut buffer = String::new();
-    let stdin = io::stdin();
-    println!("    Warning: Directory {} does not exist", path);
-    println!("Create this directory? [y/n]: ");
-    stdin.read_line(&mut buffer)?;
-    match &buffer.trim().to_lowercase()[..] {
-        "y" => create_directory(path),
-        _ => Err(error),
+fn ask_to_create_directory(
+    path: &str,
+    error: ConfigError,
+    prompter: &mut dyn Prompter,
+) -> Result<(), ConfigError> {
+    if prompter.create_directory(path)? {
+        create_directory(path)
+    } else {
+        Err(error)
     }
 }
 
-fn create_default_config(home: &str, path: &str) -> Result<File, ConfigError> {
+fn create_default_config(
+    home: &str,
+    examtrainer_dir: &str,
+    path: &str,
+) -> Result<File, ConfigError> {
     println!("Creating default configuration...");
     let mut file = File::create(path)?;
     let default_config = format!(
         "[directories]
 submit_directory = \"{0}/rendu\"
-question_directory = \"{0}/.config/examtrainer/questions\"
-exam_directory = \"{0}/.config/examtrainer/exams\"
+question_directory = \"{1}/questions\"
+exam_directory = \"{1}/exams\"
 subject_directory = \"{0}/subjects\"
 trace_directory = \"{0}/trace\"
 ",
-        home
+        home, examtrainer_dir
     );
     file.write_all(default_config.as_bytes())?;
     println!("Success!");
     File::open(path).map_err(|e| e.into())
 }
 
-fn ask_to_create_default_config(home: &str, path: &str) -> Result<File, ConfigError> {
-    let mut buffer = String::new();
-    let stdin = io::stdin();
-    println!("    Warning: Config file {} does not exist", path);
-    println!("Create default configuration file? [y/n]: ");
-    stdin.read_line(&mut buffer)?;
-    match &buffer.trim().to_lowercase()[..] {
-        "y" => create_default_config(home, path),
-        _ => Err(ConfigError::ConfigFileNotFound),
+/// Create a default config file at `path`, with its question and exam directories next to it
+fn create_config_at(path: &str) -> Result<File, ConfigError> {
+    let home = home_dir().ok_or(ConfigError::NoHomeDirectory)?;
+    let config_dir = match std::env::current_dir()?.join(path).parent() {
+        Some(parent) => parent.to_path_buf(),
+        None => return Err(ConfigError::InvalidPath(path.to_string())),
+    };
+    let config_dir = config_dir
+        .to_str()
+        .ok_or_else(|| ConfigError::InvalidPath(path.to_string()))?;
+    create_directory(config_dir)?;
+    create_default_config(home.to_str().unwrap(), config_dir, path)
+}
+
+fn ask_to_create_default_config(
+    home: &str,
+    examtrainer_dir: &str,
+    path: &str,
+    prompter: &mut dyn Prompter,
+) -> Result<File, ConfigError> {
+    if prompter.create_default_config(path)? {
+        create_default_config(home, examtrainer_dir, path)
+    } else {
+        Err(ConfigError::ConfigFileNotFound)
     }
 }
 
@@ -143,22 +370,13 @@ mod tests {
     fn initialize_toml() -> Result<(), ConfigError> {
         let buffer = std::fs::read_to_string("tst/resources/test_config2.toml")?;
         let config_toml: Config = toml_parse::from_str(&buffer)?;
-        assert_eq!(
-            config_toml.directories.submit_directory,
-            "tst/resources/rendu_test"
-        );
+        assert_eq!(config_toml.directories.submit_directory, "rendu_test");
         assert_eq!(
             config_toml.directories.question_directory,
-            "tst/resources/questions"
-        );
-        assert_eq!(
-            config_toml.directories.exam_directory,
-            "tst/resources/exams"
-        );
-        assert_eq!(
-            config_toml.directories.subject_directory,
-            "tst/resources/subjects"
+            vec!["questions"]
         );
+        assert_eq!(config_toml.directories.exam_directory, vec!["exams"]);
+        assert_eq!(config_toml.directories.subject_directory, "subjects");
         assert!(config_toml.frameworks.is_some());
         let frameworks = config_toml.frameworks.unwrap().0;
         assert_eq!(frameworks.len(), 1);
@@ -170,9 +388,67 @@ mod tests {
                     "-lgtest".into(),
                     "-lpthread".into(),
                     "-L/mnt/hard_drive/usr/lib/googletest/build/lib".into()
-                ]
+                ],
+                ..Default::default()
             }
         );
         Ok(())
     }
+
+    #[test]
+    fn directory_overrides() -> Result<(), ConfigError> {
+        let buffer = std::fs::read_to_string("tst/resources/test_config1.toml")?;
+        let mut config_toml: Config = toml_parse::from_str(&buffer)?;
+        let config_dir = Path::new("tst/resources").canonicalize()?;
+        config_toml
+            .directories
+            .resolve(&config_dir, Path::new("/tmp"), |var| match var {
+                "EXAMTRAINER_SUBMIT_DIR" => Some("/tmp/rendu".to_string()),
+                "EXAMTRAINER_TRACE_DIR" => Some("trace".to_string()),
+                _ => None,
+            })?;
+        assert_eq!(config_toml.directories.submit_directory, "/tmp/rendu");
+        assert_eq!(config_toml.directories.trace_directory, "/tmp/trace");
+        assert_eq!(
+            config_toml.directories.question_directory,
+            vec![config_dir.join("questions").to_str().unwrap()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn directory_lists() -> Result<(), ConfigError> {
+        let buffer = std::fs::read_to_string("tst/resources/test_config3.toml")?;
+        let mut config_toml: Config = toml_parse::from_str(&buffer)?;
+        assert_eq!(
+            config_toml.directories.question_directory,
+            vec!["questions_extra", "questions"]
+        );
+        assert_eq!(config_toml.directories.exam_directory, vec!["exams"]);
+        let config_dir = Path::new("tst/resources").canonicalize()?;
+        config_toml
+            .directories
+            .resolve(&config_dir, Path::new("/tmp"), |var| match var {
+                "EXAMTRAINER_EXAM_DIR" => Some("/tmp/exams:exams_extra".to_string()),
+                _ => None,
+            })?;
+        assert_eq!(
+            config_toml.directories.exam_directory,
+            vec!["/tmp/exams", "/tmp/exams_extra"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn xdg_config_home() {
+        let home = Path::new("/home/rlucas");
+        assert_eq!(config_home(home, |_| None), "/home/rlucas/.config");
+        assert_eq!(
+            config_home(home, |var| match var {
+                "XDG_CONFIG_HOME" => Some("/tmp/xdg".to_string()),
+                _ => None,
+            }),
+            "/tmp/xdg"
+        );
+    }
 }
@@ -0,0 +1,69 @@
+use super::toml;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A named compiler, along with the flags every question using it is compiled with
+#[derive(Debug, PartialEq)]
+pub struct Toolchain {
+    compiler: String,
+    flags: Vec<String>,
+}
+
+impl Toolchain {
+    fn build_from_toml(toml: toml::Toolchain) -> Self {
+        let mut flags = Vec::new();
+        if let Some(standard) = toml.standard {
+            flags.push(format!("-std={}", standard));
+        }
+        flags.extend(toml.flags);
+        Self {
+            compiler: toml.compiler,
+            flags,
+        }
+    }
+
+    pub fn compiler(&self) -> &str {
+        &self.compiler
+    }
+
+    /// Default flags, starting with `-std=<standard>` if a standard was given
+    pub fn flags(&self) -> &[String] {
+        &self.flags
+    }
+}
+
+impl fmt::Display for Toolchain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.compiler)?;
+        for flag in self.flags.iter() {
+            write!(f, " {}", flag)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn build_toolchains(
+    toml: Option<HashMap<String, toml::Toolchain>>,
+) -> HashMap<String, Toolchain> {
+    toml.unwrap_or_default()
+        .into_iter()
+        .map(|(name, toolchain)| (name, Toolchain::build_from_toml(toolchain)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_comes_first() {
+        let toolchain = Toolchain::build_from_toml(toml::Toolchain {
+            compiler: "clang".into(),
+            flags: vec!["-Wall".into(), "-Werror".into()],
+            standard: Some("c99".into()),
+        });
+        assert_eq!(toolchain.compiler(), "clang");
+        assert_eq!(toolchain.flags(), ["-std=c99", "-Wall", "-Werror"]);
+        assert_eq!(toolchain.to_string(), "clang -std=c99 -Wall -Werror");
+    }
+}
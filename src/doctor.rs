@@ -0,0 +1,292 @@
+//! Environment diagnostics for `examtrainer`
+//!
+//! [`run`] checks everything grading relies on, so that a missing compiler or library is found
+//! before an exam, rather than during one:
+//! * Every compiler named in a question's `[test]` or `[submission]` can be found
+//! * `nm` can be found, if a question limits which functions a submission may call or export
+//! * `make` can be found, if a question is submitted with a Makefile
+//! * `ar` can be found, if a question's submission is built into a library
+//! * Every framework in [`Config`] can be linked against
+//! * Every interpreter in [`Config`] can be found
+//! * The leak checker in [`Config`] can be found, if a question checks for leaks
+//! * The submit, subject and trace directories are writable
+//! * `mkdir`, `cp` and `rm`, which are used to set up questions, can be found
+
+use crate::config::Config;
+use crate::question::QuestionDB;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use std::collections::BTreeMap;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Programs that `examtrainer` shells out to when setting up and removing questions
+const REQUIRED_PROGRAMS: [&str; 3] = ["mkdir", "cp", "rm"];
+
+/// Program used to list the functions a submission calls
+const SYMBOL_LISTER: &str = "nm";
+
+/// Program used to build submissions with a Makefile
+const MAKE: &str = "make";
+
+/// Program used to build library submissions
+const ARCHIVER: &str = "ar";
+
+/// Compiler used to link frameworks that are not used by any question
+const DEFAULT_LINKER: &str = "cc";
+
+/// The outcome of a single diagnostic check.
+///
+/// `result` holds a short detail in both cases: where something was found when the check
+/// passed, or why it failed.
+#[derive(Debug)]
+pub struct Check {
+    pub name: String,
+    pub result: Result<String, String>,
+}
+
+impl Check {
+    pub fn is_passed(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Run every diagnostic check, returning them in the order they should be displayed.
+pub fn run(config: &Config, questions: &QuestionDB) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    // Maps each compiler to the first question needing it, and each framework to a compiler a
+    // question uses to link it.
+    let mut compilers = BTreeMap::new();
+    let mut linkers = BTreeMap::new();
+    let mut restricts_functions = None;
+    let mut leak_check = None;
+    let mut uses_make = None;
+    let mut uses_archiver = None;
+    for question in questions.questions() {
+        if question.uses_make() && uses_make.is_none() {
+            uses_make = Some(question.name());
+        }
+        if question.uses_archiver() && uses_archiver.is_none() {
+            uses_archiver = Some(question.name());
+        }
+        if question.leak_check() && leak_check.is_none() {
+            leak_check = Some(question.name());
+        }
+        if question.restricts_functions() && restricts_functions.is_none() {
+            restricts_functions = Some(question.name());
+        }
+        for compiler in question.compilers() {
+            compilers.entry(compiler).or_insert_with(|| question.name());
+        }
+        if let (Some(framework), Some(compiler)) =
+            (question.framework(), question.compilers().pop())
+        {
+            linkers.entry(framework).or_insert(compiler);
+        }
+    }
+
+    for (compiler, question) in compilers {
+        checks.push(Check {
+            name: format!("compiler {}", compiler),
+            result: find_program(compiler)
+                .map(|path| path.display().to_string())
+                .ok_or_else(|| format!("not found, needed by {}", question)),
+        });
+    }
+    if let Some(question) = restricts_functions {
+        checks.push(Check {
+            name: format!("program {}", SYMBOL_LISTER),
+            result: find_program(SYMBOL_LISTER)
+                .map(|path| path.display().to_string())
+                .ok_or_else(|| format!("not found, needed by {}", question)),
+        });
+    }
+    for (program, question) in [(MAKE, uses_make), (ARCHIVER, uses_archiver)] {
+        if let Some(question) = question {
+            checks.push(Check {
+                name: format!("program {}", program),
+                result: find_program(program)
+                    .map(|path| path.display().to_string())
+                    .ok_or_else(|| format!("not found, needed by {}", question)),
+            });
+        }
+    }
+    for (name, framework) in config.frameworks() {
+        let linker = linkers.get(name).copied().unwrap_or(DEFAULT_LINKER);
+        let flags: Vec<String> = framework.flags().cloned().collect();
+        checks.push(Check {
+            name: format!("framework {}", name),
+            result: check_framework(linker, &flags),
+        });
+    }
+    for (name, interpreter) in config.interpreters() {
+        checks.push(Check {
+            name: format!("interpreter {}", name),
+            result: find_program(interpreter.program())
+                .map(|path| path.display().to_string())
+                .ok_or_else(|| format!("{} not found", interpreter.program())),
+        });
+    }
+    if let Some(question) = leak_check {
+        let checker = config.leak_checker();
+        checks.push(Check {
+            name: "leak checker".to_string(),
+            result: find_program(checker.program())
+                .map(|path| path.display().to_string())
+                .ok_or_else(|| format!("{} not found, needed by {}", checker.program(), question)),
+        });
+    }
+    for (name, dir) in [
+        ("submit", config.submit_dir()),
+        ("subject", config.subject_dir()),
+        ("trace", config.trace_dir()),
+    ] {
+        checks.push(Check {
+            name: format!("{} directory", name),
+            result: check_writable(Path::new(dir)),
+        });
+    }
+    for program in REQUIRED_PROGRAMS {
+        checks.push(Check {
+            name: format!("program {}", program),
+            result: find_program(program)
+                .map(|path| path.display().to_string())
+                .ok_or_else(|| "not found in PATH".to_string()),
+        });
+    }
+    checks
+}
+
+/// Find `program` the way [`Command`] would, searching `PATH` unless it contains a `/`.
+fn find_program(program: &str) -> Option<PathBuf> {
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return is_executable(&path).then_some(path);
+    }
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(program))
+        .find(|path| is_executable(path))
+}
+
+fn is_executable(path: &Path) -> bool {
+    match path.metadata() {
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+fn random_name() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(10)
+        .map(char::from)
+        .collect()
+}
+
+/// Check that a file can be created in `dir`.
+///
+/// Directories that do not exist yet are created when a question is started, so in that case
+/// the closest existing parent is checked instead.
+fn check_writable(dir: &Path) -> Result<String, String> {
+    let existing = dir
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .ok_or_else(|| format!("{} does not exist", dir.display()))?;
+    if !existing.is_dir() {
+        return Err(format!("{} is not a directory", existing.display()));
+    }
+    let probe = existing.join(format!(".examtrainer-{}", random_name()));
+    std::fs::File::create(&probe)
+        .map_err(|e| format!("{} is not writable: {}", existing.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+    if existing == dir {
+        Ok(dir.display().to_string())
+    } else {
+        Ok(format!("{} (will be created)", dir.display()))
+    }
+}
+
+/// Check that an empty program can be linked with a framework's flags.
+fn check_framework(linker: &str, flags: &[String]) -> Result<String, String> {
+    if find_program(linker).is_none() {
+        return Err(format!("linker {} not found", linker));
+    }
+    let dir = std::env::temp_dir().join(format!("examtrainer-{}", random_name()));
+    std::fs::create_dir(&dir).map_err(|e| e.to_string())?;
+    let source = dir.join("main.c");
+    let result = std::fs::write(&source, "int main(void) { return 0; }\n")
+        .map_err(|e| e.to_string())
+        .and_then(|_| {
+            Command::new(linker)
+                .arg("-x")
+                .arg("c")
+                .arg(&source)
+                .arg("-x")
+                .arg("none")
+                .arg("-o")
+                .arg(dir.join("a.out"))
+                .args(flags)
+                .output()
+                .map_err(|e| e.to_string())
+        });
+    let _ = std::fs::remove_dir_all(&dir);
+    let output = result?;
+    if output.status.success() {
+        Ok(format!("linked with {}", linker))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(stderr.lines().next().unwrap_or("link failed").to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_programs() {
+        assert!(find_program("sh").is_some());
+        assert!(find_program("/bin/sh").is_some());
+        assert!(find_program("examtrainer-nonexistent-program").is_none());
+    }
+
+    #[test]
+    fn writable_directories() {
+        let dir = std::env::temp_dir();
+        assert!(check_writable(&dir).is_ok());
+        let missing = dir.join("examtrainer-missing").join("nested");
+        assert!(check_writable(&missing)
+            .unwrap()
+            .ends_with("(will be created)"));
+        let file = Path::new("tst/resources/test_config1.toml");
+        assert!(check_writable(&file.join("subdir")).is_err());
+    }
+
+    #[test]
+    fn frameworks() {
+        assert!(check_framework("gcc", &["-lm".to_string()]).is_ok());
+        assert!(check_framework("gcc", &["-lexamtrainer_missing".to_string()]).is_err());
+        assert!(check_framework("examtrainer-nonexistent-cc", &[]).is_err());
+    }
+
+    #[test]
+    fn doctor_test_config() -> Result<(), crate::Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let questions = QuestionDB::new(&config)?;
+        let checks = run(&config, &questions);
+        for name in [
+            "compiler gcc",
+            "submit directory",
+            "program mkdir",
+            "program nm",
+        ] {
+            let check = checks.iter().find(|check| check.name == name);
+            assert!(check.is_some(), "missing check {}", name);
+            assert!(check.unwrap().is_passed());
+        }
+        Ok(())
+    }
+}
@@ -2,12 +2,14 @@ use std::fmt;
 use std::io;
 use std::sync::PoisonError;
 
+use crate::cli::CliError;
 use crate::config::ConfigError;
 use crate::exam::ExamError;
 use crate::question::QuestionError;
 
 #[derive(Debug)]
 pub enum Error {
+    Cli(CliError),
     Config(ConfigError),
     IO(io::Error),
     Question(QuestionError),
@@ -19,6 +21,7 @@ pub enum Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Self::Cli(cli_error) => write!(f, "{}", cli_error),
             Self::Config(config_error) => write!(f, "Config Error: {}", config_error),
             Self::IO(io_e) => write!(f, "IO Error: {}", io_e),
             Self::Question(q_e) => write!(f, "Question Error: {}", q_e),
@@ -41,6 +44,12 @@ impl<T> From<PoisonError<T>> for Error {
     }
 }
 
+impl From<CliError> for Error {
+    fn from(input: CliError) -> Error {
+        Error::Cli(input)
+    }
+}
+
 impl From<ConfigError> for Error {
     fn from(input: ConfigError) -> Error {
         match input {
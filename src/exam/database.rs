@@ -5,6 +5,7 @@ use colored::*;
 use std::collections::HashMap;
 use std::fmt;
 use std::fs::DirEntry;
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub struct ExamDB {
@@ -12,19 +13,25 @@ pub struct ExamDB {
 }
 
 impl ExamDB {
+    /// Generate a database of [`Exam`]'s from every exam file in each `exam_directory` specified
+    /// in [`Config`].
+    ///
+    /// Exam directories are searched in the order they are listed in [`Config`]. If two exams
+    /// have the same name, the first one found is kept.
     pub fn new(config: &Config, database: &QuestionDB) -> Result<Self, ExamError> {
-        let exam_files = std::fs::read_dir(config.exam_dir())?.filter(|entry| {
-            if let Ok(file) = entry {
-                file.path().is_file()
-            } else {
-                false
-            }
-        });
         let mut exams = HashMap::new();
-        for file in exam_files.into_iter().flatten() {
-            match Exam::build_from_dir_entry(&file, database) {
-                Ok(exam) => insert_new_exam(&mut exams, exam),
-                Err(e) => print_exam_error(&file, e),
+        let mut origins = HashMap::new();
+        for exam_dir in config.exam_dirs() {
+            let mut exam_files: Vec<DirEntry> = std::fs::read_dir(exam_dir)?
+                .flatten()
+                .filter(|entry| entry.path().is_file())
+                .collect();
+            exam_files.sort_by_key(|entry| entry.path());
+            for file in exam_files.iter() {
+                match Exam::build_from_dir_entry(file, database) {
+                    Ok(exam) => insert_new_exam(&mut exams, &mut origins, exam, file.path()),
+                    Err(e) => print_exam_error(file, e),
+                }
             }
         }
         Ok(Self { exams })
@@ -44,17 +51,25 @@ impl fmt::Display for ExamDB {
     }
 }
 
-fn insert_new_exam(exams: &mut HashMap<String, Exam>, exam: Exam) {
-    if exams.get(exam.name()).is_some() {
+fn insert_new_exam(
+    exams: &mut HashMap<String, Exam>,
+    origins: &mut HashMap<String, PathBuf>,
+    exam: Exam,
+    path: PathBuf,
+) {
+    if let Some(existing) = origins.get(exam.name()) {
         eprintln!(
             "{}",
             format!(
-                "Warning: The exam {} appeared twice, second instance was ignored",
-                exam.name()
+                "Warning: The exam {} in {} was ignored, as it is already defined in {}",
+                exam.name(),
+                path.display(),
+                existing.display()
             )
             .yellow()
         );
     } else {
+        origins.insert(exam.name().to_string(), path);
         exams.insert(exam.name().to_string(), exam);
     }
 }
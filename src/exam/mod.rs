@@ -186,7 +186,7 @@ mod tests {
     fn exam_create_from_dir_entry() -> Result<(), Error> {
         let config = Config::new_from("tst/resources/test_config2.toml")?;
         let question_database = QuestionDB::new(&config)?;
-        let exam_dir = std::fs::read_dir(config.exam_dir())?.filter(|entry| {
+        let exam_dir = std::fs::read_dir(config.exam_dirs()[0])?.filter(|entry| {
             if let Ok(file) = entry {
                 file.path().is_file()
             } else {
@@ -1,4 +1,6 @@
+pub mod cli;
 pub mod config;
+pub mod doctor;
 pub mod error;
 pub mod exam;
 pub mod output;
@@ -9,12 +11,70 @@ pub mod utils;
 
 pub use error::Error;
 
+use cli::Command;
 use config::Config;
 use exam::ExamDB;
+use question::test::TestResult;
 use question::QuestionDB;
 
-pub fn run(config: Config, questions: QuestionDB, exams: ExamDB) -> Result<(), Error> {
-    output::intro();
+/// Run `command`, returning whether it passed: `false` if the submission graded by
+/// [`Command::Grade`] failed, or if a check run by [`Command::Doctor`] did.
+pub fn run(
+    config: Config,
+    questions: QuestionDB,
+    exams: ExamDB,
+    command: Command,
+) -> Result<bool, Error> {
+    match command {
+        Command::MainMenu => {
+            output::intro();
+            shell::main_menu::run(config, questions, exams)?;
+            Ok(true)
+        }
+        command => shell::run_command(config, questions, exams, command),
+    }
+}
+
+/// Finish setting up `examtrainer` for `config`, by creating the submit, subject and trace
+/// directories.
+///
+/// The config file and the question and exam directories are created while [`Config`] is read,
+/// so to set up without any interaction, read it with [`config::FixedAnswer`].
+pub fn init(config: &Config) -> Result<(), Error> {
+    shell::create_standard_directories(config)?;
+    output::init_complete(config);
+    Ok(())
+}
+
+/// Grade the current submission for the question `question_name`, without any user interaction.
+///
+/// The submission is read from the question's subdirectory of the configured submit directory,
+/// exactly as `grademe` would. Returns [`Error::General`] if no question has the given name.
+pub fn grade(
+    config: &Config,
+    questions: &QuestionDB,
+    question_name: &str,
+) -> Result<TestResult, Error> {
+    let question = questions
+        .get_question_by_name(question_name)
+        .ok_or_else(|| Error::General(format!("The question '{}' was not found", question_name)))?;
+    Ok(question.grade(config)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    shell::main_menu::run(config, questions, exams)
+    #[test]
+    fn grade_without_interaction() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let questions = QuestionDB::new(&config)?;
+        assert!(grade(&config, &questions, "hello_world")?.is_passed());
+        assert!(!grade(&config, &questions, "Z_failed_countdown")?.is_passed());
+        assert!(matches!(
+            grade(&config, &questions, "nonexistent_question"),
+            Err(Error::General(_))
+        ));
+        Ok(())
+    }
 }
@@ -1,24 +1,59 @@
-use examtrainer::config::Config;
+use examtrainer::cli::{Args, Command};
+use examtrainer::config::{Config, FixedAnswer, Prompter, StdinPrompter};
 use examtrainer::exam::ExamDB;
+use examtrainer::output;
 use examtrainer::question::QuestionDB;
 use std::process::exit;
 
-// TODO take first argument as Config file location (or accept flags potentially)
+const EXIT_FAILED: i32 = 1;
+const EXIT_ERROR: i32 = 2;
+
 fn main() {
-    let config = Config::new().unwrap_or_else(|e| {
+    let args: Vec<String> = std::env::args().collect();
+    let args = Args::parse(&args).unwrap_or_else(|e| {
         eprintln!("Error: {}", e);
-        exit(1);
+        eprintln!("Try 'examtrainer --help' for more information");
+        exit(EXIT_ERROR);
     });
+    if args.command == Command::Help {
+        output::usage();
+        return;
+    }
+    let mut prompter: Box<dyn Prompter> = match (args.answer, &args.command) {
+        (Some(answer), _) => Box::new(FixedAnswer(answer)),
+        (None, Command::Init) => Box::new(FixedAnswer(true)),
+        (None, Command::Grade(_)) => Box::new(FixedAnswer(false)),
+        (None, _) => Box::new(StdinPrompter),
+    };
+    let config = match args.command {
+        Command::Init => Config::for_init(args.config.as_deref(), prompter.as_mut()),
+        _ => Config::with_prompter(args.config.as_deref(), prompter.as_mut()),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        exit(EXIT_ERROR);
+    });
+    if args.command == Command::Init {
+        if let Err(e) = examtrainer::init(&config) {
+            eprintln!("Error: {}", e);
+            exit(EXIT_ERROR);
+        }
+        return;
+    }
     let questions = QuestionDB::new(&config).unwrap_or_else(|e| {
         eprintln!("Error: {}", e);
-        exit(1);
+        exit(EXIT_ERROR);
     });
     let exams = ExamDB::new(&config, &questions).unwrap_or_else(|e| {
         eprintln!("Error: {}", e);
-        exit(1);
+        exit(EXIT_ERROR);
     });
-    if let Err(e) = examtrainer::run(config, questions, exams) {
-        eprintln!("Error: {}", e);
-        exit(1);
+    match examtrainer::run(config, questions, exams, args.command) {
+        Ok(true) => (),
+        Ok(false) => exit(EXIT_FAILED),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(EXIT_ERROR);
+        }
     }
 }
@@ -47,3 +47,42 @@ pub fn exam_help() {
         "quit".green()
     );
 }
+
+pub fn usage() {
+    println!("Usage: examtrainer [--config <path>] [command]\n");
+    println!("Options:");
+    println!(
+        "  {} - Use the config file at <path>",
+        "-c, --config <path>".green()
+    );
+    println!(
+        "  {} - Answer yes/no to creating a missing config file or directory, without asking",
+        "-y, --yes / --no".green()
+    );
+    println!("  {} - Show this message\n", "-h, --help".green());
+    println!("Commands (the main menu is started if no command is given):");
+    println!(
+        "  {} - Create the config file and directories without asking (unless --no is given)",
+        "init".green()
+    );
+    println!("  {} - List all questions", "list questions".green());
+    println!("  {} - List all exams", "list exams".green());
+    println!(
+        "  {} - Practice a single question",
+        "question <question_name>".green()
+    );
+    println!("  {} - Start an exam", "exam <exam_name>".green());
+    println!(
+        "  {} - Grade the current submission for a question, exiting with 0 if it passed",
+        "grade <question_name>".green()
+    );
+    println!(
+        "  {} - Display current Examtrainer configuration",
+        "config".green()
+    );
+    println!(
+        "  {} - Check that everything needed for grading is installed",
+        "doctor".green()
+    );
+    println!("  {} - Show this message", "help".green());
+}
@@ -3,7 +3,9 @@ pub mod help;
 pub use help::*;
 
 use crate::config::Config;
+use crate::doctor::Check;
 use crate::exam::Exam;
+use crate::question::test::TestResult;
 use crate::question::Question;
 use crate::user::User;
 use crate::utils;
@@ -142,6 +144,45 @@ pub fn print_failure() {
     println!("You have failed the assignment\n");
 }
 
+pub fn grade_report(question_name: &str, test_result: &TestResult) {
+    match test_result {
+        TestResult::Passed => println!("{}: {}", question_name, "PASSED".green()),
+        TestResult::Failed(test_error) => {
+            println!("{}: {}", question_name, "FAILED".red());
+            println!("{}", test_error);
+        }
+    }
+}
+
+pub fn doctor_report(checks: &[Check]) {
+    let width = checks
+        .iter()
+        .map(|check| check.name.len())
+        .max()
+        .unwrap_or(0);
+    for check in checks {
+        match &check.result {
+            Ok(detail) => println!("{:width$}  {}  {}", check.name, "PASS".green(), detail),
+            Err(reason) => println!("{:width$}  {}  {}", check.name, "FAIL".red(), reason),
+        }
+    }
+    let failed = checks.iter().filter(|check| !check.is_passed()).count();
+    if failed == 0 {
+        println!("\nAll {} checks passed", checks.len());
+    } else {
+        println!(
+            "\n{} of {} checks failed",
+            failed.to_string().red(),
+            checks.len()
+        );
+    }
+}
+
+pub fn init_complete(config: &Config) {
+    println!("{}", config);
+    println!("Examtrainer is set up, and ready to use");
+}
+
 pub fn print_config_info(config: &Config) {
     println!("{}", config);
 }
@@ -206,7 +247,7 @@ pub fn exam_status(config: &Config, user: &User, exam: &Exam, time_info: &utils:
         );
         user.print_history();
         print_directory_info(config, question);
-        println!("");
+        println!();
         print_time_info(time_info);
         print_divider_bar();
     }
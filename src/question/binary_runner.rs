@@ -1,32 +1,128 @@
+use crate::config::{Interpreter, LeakChecker};
+use crate::question::case::Case;
+use crate::question::tree::Workspace;
 use crate::question::QuestionError;
 use crate::utils::ProgramOutput;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::thread;
 use std::time::{Duration, Instant};
 
-// This time is for each instance of a binary running, this could perhaps be a part of the Question
-// instead of a constant.
-const TIMEOUT: u64 = 10;
-
 #[derive(Debug)]
 pub enum BinaryResult {
     Output(ProgramOutput),
     Timeout,
 }
 
-pub fn run_binary_with_args(binary: &str, args: &[String]) -> Result<BinaryResult, QuestionError> {
-    let mut exec = Command::new(&binary);
-    for arg in args.iter() {
+/// A submission to run for each case: either a binary, a script run by an interpreter, or a
+/// binary run by a leak checker
+#[derive(Debug, Clone, Copy)]
+pub enum Program<'a> {
+    Binary(&'a str),
+    Script {
+        interpreter: &'a Interpreter,
+        script: &'a str,
+    },
+    Checked {
+        checker: &'a LeakChecker,
+        binary: &'a str,
+    },
+}
+
+impl Program<'_> {
+    /// Run the program in a new working directory, which is removed afterwards along with
+    /// anything the program wrote there, other than the files `case` expects
+    pub fn run(&self, case: &Case) -> Result<BinaryResult, QuestionError> {
+        let workspace = Workspace::new(case.initial_tree.as_deref())?;
+        let result = self.run_in(case, workspace.dir());
+        workspace.remove()?;
+        result
+    }
+
+    /// Run the program with `dir` as its working directory, reading back the files that `case`
+    /// expects it to write there
+    pub fn run_in(&self, case: &Case, dir: &Path) -> Result<BinaryResult, QuestionError> {
+        let mut exec = self.command();
+        exec.current_dir(dir);
+        let mut output = match run_command(exec, case)? {
+            BinaryResult::Output(output) => output,
+            BinaryResult::Timeout => return Ok(BinaryResult::Timeout),
+        };
+        for name in case.expected.files.keys() {
+            let contents = fs::read(dir.join(name))
+                .ok()
+                .map(|contents| String::from_utf8_lossy(&contents).into_owned());
+            output.add_file(name, contents);
+        }
+        Ok(BinaryResult::Output(output))
+    }
+
+    /// The command running the program. Its paths are made absolute, as a relative path could
+    /// otherwise be taken from either working directory.
+    fn command(&self) -> Command {
+        match self {
+            Self::Binary(binary) => Command::new(program_path(binary)),
+            Self::Script {
+                interpreter,
+                script,
+            } => {
+                let mut exec = Command::new(program_path(interpreter.program()));
+                exec.args(interpreter.args()).arg(absolute(script));
+                exec
+            }
+            Self::Checked { checker, binary } => {
+                let mut exec = Command::new(program_path(checker.program()));
+                exec.args(checker.args()).arg(absolute(binary));
+                exec
+            }
+        }
+    }
+}
+
+/// Run `binary` with the arguments and stdin of `case` in a new working directory, killing it if
+/// it has not finished within the case's timeout
+pub fn run_binary(binary: &str, case: &Case) -> Result<BinaryResult, QuestionError> {
+    Program::Binary(binary).run(case)
+}
+
+/// The path of `program`, or its name if it has no directory and is looked up in `PATH`
+fn program_path(program: &str) -> PathBuf {
+    if program.contains('/') {
+        absolute(program)
+    } else {
+        PathBuf::from(program)
+    }
+}
+
+fn absolute(path: &str) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path))
+}
+
+fn run_command(mut exec: Command, case: &Case) -> Result<BinaryResult, QuestionError> {
+    for arg in case.args.iter() {
         exec.arg(arg);
     }
+    match case.stdin {
+        Some(_) => exec.stdin(Stdio::piped()),
+        None => exec.stdin(Stdio::null()),
+    };
     exec.stdout(Stdio::piped()).stderr(Stdio::piped());
     let mut child = exec.spawn()?;
 
+    // Written from another thread, so that a binary which doesn't read all of its input can't
+    // block the timeout below. The write fails once the binary exits, which can be ignored.
+    if let (Some(input), Some(mut pipe)) = (case.stdin.clone(), child.stdin.take()) {
+        thread::spawn(move || {
+            let _ = pipe.write_all(input.as_bytes());
+        });
+    }
+
     let start = Instant::now();
-    let timeout = Duration::from_secs(TIMEOUT);
     let mut process_finished = false;
 
-    while start.elapsed() < timeout {
+    while start.elapsed() < case.timeout {
         match child.try_wait() {
             Ok(Some(_)) => {
                 process_finished = true;
@@ -44,3 +140,31 @@ pub fn run_binary_with_args(binary: &str, args: &[String]) -> Result<BinaryResul
         Ok(BinaryResult::Timeout)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_cat(stdin: Option<&str>) -> Result<ProgramOutput, QuestionError> {
+        let case = Case {
+            stdin: stdin.map(String::from),
+            ..Case::empty(Duration::from_secs(5))
+        };
+        match run_binary("cat", &case)? {
+            BinaryResult::Output(output) => Ok(output),
+            BinaryResult::Timeout => panic!("cat should not time out"),
+        }
+    }
+
+    #[test]
+    fn stdin_is_piped() -> Result<(), QuestionError> {
+        assert_eq!(run_cat(Some("hello\nworld\n"))?.stdout(), "hello\nworld\n");
+        Ok(())
+    }
+
+    #[test]
+    fn stdin_is_empty_by_default() -> Result<(), QuestionError> {
+        assert_eq!(run_cat(None)?.stdout(), "");
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+//! A single run of a binary during a [`Test`](super::test::Test)
+//!
+//! Cases come from either entries of `args`, or a `[[test.cases]]` table in a Question .toml
+//! file, but not both:
+//! ```toml
+//! [[test.cases]]
+//! name = "two words"
+//! args = ["hello", "world"]
+//! stdout = "hello\n"
+//! exit_code = 0
+//!
+//! [[test.cases]]
+//! name = "no arguments"
+//! stdout_file = "no_arguments.out"
+//!
+//! [[test.cases]]
+//! name = "either order"
+//! args = ["a", "b"]
+//! stdout = ["a\nb\n", "b\na\n"]
+//!
+//! [[test.cases]]
+//! name = "copy"
+//! args = ["in.txt", "out.txt"]
+//! initial_tree = "copy"
+//! files = { "out.txt" = "hello\n" }
+//! ```
+//!
+//! Every case runs in a new working directory, which holds a copy of the case's `initial_tree`
+//! if it has one. `files` are those the case expects the program to write there.
+
+use crate::question::compare::Compare;
+use crate::question::toml;
+use crate::question::tree::Difference;
+use crate::question::QuestionError;
+use crate::utils;
+use crate::utils::ProgramOutput;
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+use std::time::Duration;
+
+/// What a [`Case`] expects of the submission's output. Only the parts that are given are checked,
+/// and stdout or stderr matches if it matches any of the accepted outputs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Expected {
+    pub stdout: Option<Vec<String>>,
+    pub stderr: Option<Vec<String>>,
+    pub exit_code: Option<i32>,
+    /// The contents of each file the submission should write, by its path in the working directory
+    pub files: BTreeMap<String, String>,
+    pub compare: Compare,
+}
+
+impl Expected {
+    pub fn is_empty(&self) -> bool {
+        self.stdout.is_none()
+            && self.stderr.is_none()
+            && self.exit_code.is_none()
+            && self.files.is_empty()
+    }
+
+    fn any_matches(&self, accepted: &Option<Vec<String>>, actual: &str) -> bool {
+        accepted.as_ref().is_none_or(|accepted| {
+            accepted
+                .iter()
+                .any(|expected| self.compare.matches(expected, actual))
+        })
+    }
+
+    pub fn stdout_matches(&self, actual: &str) -> bool {
+        self.any_matches(&self.stdout, actual)
+    }
+
+    pub fn stderr_matches(&self, actual: &str) -> bool {
+        self.any_matches(&self.stderr, actual)
+    }
+
+    pub fn exit_code_matches(&self, actual: i32) -> bool {
+        self.exit_code.is_none_or(|code| code == actual)
+    }
+
+    /// Each expected file that `output` is missing, or has different contents for
+    pub fn file_differences(&self, output: &ProgramOutput) -> Vec<Difference> {
+        self.files
+            .iter()
+            .filter_map(|(name, expected)| match output.file(name) {
+                None => Some(Difference::Missing(name.clone())),
+                Some(actual) if !self.compare.matches(expected, actual) => {
+                    Some(Difference::Contents {
+                        path: name.clone(),
+                        expected: expected.clone(),
+                        actual: actual.to_string(),
+                    })
+                }
+                Some(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn matches(&self, output: &ProgramOutput) -> bool {
+        self.stdout_matches(output.stdout())
+            && self.stderr_matches(output.stderr())
+            && self.exit_code_matches(output.code())
+            && self.file_differences(output).is_empty()
+    }
+}
+
+/// One run of the binary under test: the arguments and stdin it is given, how long it may run
+/// for, and what its output should be
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    pub name: String,
+    pub args: Vec<String>,
+    /// Input written to stdin. When there is none, stdin is empty rather than the terminal.
+    pub stdin: Option<String>,
+    pub timeout: Duration,
+    pub expected: Expected,
+    /// The seed a generated case's arguments came from
+    pub seed: Option<u64>,
+    /// Directory copied into the working directory of the run
+    pub initial_tree: Option<String>,
+}
+
+impl Case {
+    /// A [`Case`] with no arguments, input or expected output
+    pub fn empty(timeout: Duration) -> Self {
+        Self {
+            name: String::new(),
+            args: Vec::new(),
+            stdin: None,
+            timeout,
+            expected: Expected::default(),
+            seed: None,
+            initial_tree: None,
+        }
+    }
+
+    /// Build the `index`th [`Case`] of a test, which runs for `timeout` and compares output with
+    /// `compare` unless the case gives its own. Files named by the case are read from `dir_path`,
+    /// the question directory.
+    pub fn build_from_toml(
+        toml: toml::Case,
+        index: usize,
+        dir_path: &str,
+        timeout: Duration,
+        compare: Compare,
+    ) -> Result<Self, QuestionError> {
+        let expected = Expected {
+            stdout: read_accepted("stdout", toml.stdout, toml.stdout_file, dir_path)?,
+            stderr: read_accepted("stderr", toml.stderr, toml.stderr_file, dir_path)?,
+            exit_code: toml.exit_code,
+            files: toml.files.unwrap_or_default(),
+            compare: Compare::build_from_toml(toml.compare.as_deref(), toml.tolerance, compare)?,
+        };
+        for accepted in expected.stdout.iter().chain(expected.stderr.iter()) {
+            for text in accepted.iter() {
+                expected.compare.validate(text)?;
+            }
+        }
+        for (name, contents) in expected.files.iter() {
+            validate_file_name(name)?;
+            expected.compare.validate(contents)?;
+        }
+        let initial_tree = toml
+            .initial_tree
+            .map(|tree| format!("{}/{}", dir_path, tree));
+        if let Some(tree) = &initial_tree {
+            if !Path::new(tree).is_dir() {
+                return Err(QuestionError::InvalidCase(format!(
+                    "initial_tree '{}' is not a directory",
+                    tree
+                )));
+            }
+        }
+        Ok(Self {
+            name: toml.name.unwrap_or_else(|| format!("case {}", index + 1)),
+            args: toml.args,
+            stdin: read_text("stdin", toml.stdin, toml.stdin_file, dir_path)?,
+            timeout: parse_timeout(toml.timeout, timeout)?,
+            expected,
+            seed: None,
+            initial_tree,
+        })
+    }
+
+    fn build_from_arg_set(
+        toml: toml::ArgSet,
+        index: usize,
+        dir_path: &str,
+        timeout: Duration,
+        compare: Compare,
+    ) -> Result<Self, QuestionError> {
+        let toml = match toml {
+            toml::ArgSet::Args(args) => toml::Case {
+                args,
+                ..Default::default()
+            },
+            toml::ArgSet::Table(case) => *case,
+        };
+        Self::build_from_toml(toml, index, dir_path, timeout, compare)
+    }
+}
+
+/// Build the cases of a `[test]` table from either `args` or `cases`, returning [`None`] if
+/// neither was given.
+pub fn build_cases(
+    args: Option<Vec<toml::ArgSet>>,
+    cases: Option<Vec<toml::Case>>,
+    dir_path: &str,
+    timeout: Duration,
+    compare: Compare,
+) -> Result<Option<Vec<Case>>, QuestionError> {
+    match (args, cases) {
+        (Some(_), Some(_)) => Err(QuestionError::InvalidCase(
+            "only one of 'args' and 'cases' may be given".into(),
+        )),
+        (Some(args), None) => args
+            .into_iter()
+            .enumerate()
+            .map(|(index, arg_set)| {
+                Case::build_from_arg_set(arg_set, index, dir_path, timeout, compare)
+            })
+            .collect::<Result<_, _>>()
+            .map(Some),
+        (None, Some(cases)) => cases
+            .into_iter()
+            .enumerate()
+            .map(|(index, case)| Case::build_from_toml(case, index, dir_path, timeout, compare))
+            .collect::<Result<_, _>>()
+            .map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+fn both_given(key: &str) -> QuestionError {
+    QuestionError::InvalidCase(format!(
+        "only one of '{0}' and '{0}_file' may be given",
+        key
+    ))
+}
+
+fn read_file(key: &str, file: &str, dir_path: &str) -> Result<String, QuestionError> {
+    let path = format!("{}/{}", dir_path, file);
+    std::fs::read_to_string(&path).map_err(|e| {
+        QuestionError::InvalidCase(format!("{}_file '{}' could not be read: {}", key, path, e))
+    })
+}
+
+/// Read text given either directly, or as a file in `dir_path`, but not both
+fn read_text(
+    key: &str,
+    text: Option<String>,
+    file: Option<String>,
+    dir_path: &str,
+) -> Result<Option<String>, QuestionError> {
+    match (text, file) {
+        (Some(_), Some(_)) => Err(both_given(key)),
+        (Some(text), None) => Ok(Some(text)),
+        (None, Some(file)) => read_file(key, &file, dir_path).map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Read the accepted outputs given either directly, or as files in `dir_path`, but not both
+fn read_accepted(
+    key: &str,
+    texts: Option<toml::OneOrMany>,
+    files: Option<toml::OneOrMany>,
+    dir_path: &str,
+) -> Result<Option<Vec<String>>, QuestionError> {
+    match (texts, files) {
+        (Some(_), Some(_)) => Err(both_given(key)),
+        (Some(texts), None) => Ok(Some(texts.into_vec())),
+        (None, Some(files)) => files
+            .into_vec()
+            .iter()
+            .map(|file| read_file(key, file, dir_path))
+            .collect::<Result<_, _>>()
+            .map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Check that an expected file is written inside the working directory of the run
+fn validate_file_name(name: &str) -> Result<(), QuestionError> {
+    let mut components = Path::new(name).components();
+    if components.all(|component| matches!(component, Component::Normal(_))) && !name.is_empty() {
+        Ok(())
+    } else {
+        Err(QuestionError::InvalidCase(format!(
+            "file '{}' must be a relative path inside the working directory",
+            name
+        )))
+    }
+}
+
+/// Convert a timeout in seconds from a Question .toml file, using `default` if none was given
+pub fn parse_timeout(seconds: Option<f64>, default: Duration) -> Result<Duration, QuestionError> {
+    match seconds {
+        Some(seconds) => {
+            utils::duration_from_secs(seconds).ok_or(QuestionError::InvalidTimeout(seconds))
+        }
+        None => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIR: &str = "tst/resources/questions/rev_stdin";
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn build(toml: toml::Case) -> Result<Case, QuestionError> {
+        Case::build_from_toml(toml, 0, DIR, TIMEOUT, Compare::Exact)
+    }
+
+    #[test]
+    fn case_timeouts() -> Result<(), QuestionError> {
+        let case = Case::build_from_arg_set(
+            toml::ArgSet::Args(vec!["a".into()]),
+            0,
+            DIR,
+            TIMEOUT,
+            Compare::Exact,
+        )?;
+        assert_eq!(case.args, vec!["a"]);
+        assert_eq!(case.timeout, TIMEOUT);
+        let case = build(toml::Case {
+            timeout: Some(0.5),
+            ..Default::default()
+        })?;
+        assert_eq!(case.timeout, Duration::from_millis(500));
+        assert!(matches!(
+            parse_timeout(Some(-1.0), TIMEOUT),
+            Err(QuestionError::InvalidTimeout(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn case_stdin() -> Result<(), QuestionError> {
+        let case = build(toml::Case {
+            stdin: Some("abc\n".into()),
+            ..Default::default()
+        })?;
+        assert_eq!(case.stdin, Some("abc\n".into()));
+        let case = build(toml::Case {
+            stdin_file: Some("lines.in".into()),
+            ..Default::default()
+        })?;
+        assert_eq!(case.stdin, Some("hello\nworld\n".into()));
+        let case = build(toml::Case {
+            stdin: Some("abc".into()),
+            stdin_file: Some("lines.in".into()),
+            ..Default::default()
+        });
+        assert!(matches!(case, Err(QuestionError::InvalidCase(_))));
+        let case = build(toml::Case {
+            stdin_file: Some("missing.in".into()),
+            ..Default::default()
+        });
+        assert!(matches!(case, Err(QuestionError::InvalidCase(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn case_expectations() -> Result<(), QuestionError> {
+        let case = build(toml::Case {
+            name: Some("lines".into()),
+            stdout_file: Some(toml::OneOrMany::One("lines.in".into())),
+            exit_code: Some(0),
+            ..Default::default()
+        })?;
+        assert_eq!(case.name, "lines");
+        let output = ProgramOutput::from_strings(0, "hello\nworld\n".into(), "anything".into());
+        assert!(case.expected.matches(&output));
+        let output = ProgramOutput::from_strings(1, "hello\nworld\n".into(), String::new());
+        assert!(!case.expected.matches(&output));
+        assert!(build(toml::Case::default())?.expected.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn case_files() -> Result<(), QuestionError> {
+        let case = build(toml::Case {
+            files: Some(BTreeMap::from([("out/a.txt".into(), "a\n".into())])),
+            ..Default::default()
+        })?;
+        assert!(!case.expected.is_empty());
+        let mut output = ProgramOutput::from_strings(0, String::new(), String::new());
+        assert_eq!(
+            case.expected.file_differences(&output),
+            [Difference::Missing("out/a.txt".into())]
+        );
+        output.add_file("out/a.txt", Some("a\n".into()));
+        assert!(case.expected.matches(&output));
+        for name in ["", "/tmp/a.txt", "../a.txt", "out/../../a.txt"] {
+            let case = build(toml::Case {
+                files: Some(BTreeMap::from([(name.into(), String::new())])),
+                ..Default::default()
+            });
+            assert!(matches!(case, Err(QuestionError::InvalidCase(_))));
+        }
+        let case = build(toml::Case {
+            initial_tree: Some("missing".into()),
+            ..Default::default()
+        });
+        assert!(matches!(case, Err(QuestionError::InvalidCase(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn case_compare() -> Result<(), QuestionError> {
+        let case = build(toml::Case {
+            stdout: Some(toml::OneOrMany::Many(vec![
+                "a\nb\n".into(),
+                "b\na\n".into(),
+            ])),
+            ..Default::default()
+        })?;
+        assert!(case.expected.stdout_matches("b\na\n"));
+        assert!(!case.expected.stdout_matches("a\n"));
+        let case = Case::build_from_toml(
+            toml::Case {
+                stdout: Some(toml::OneOrMany::One("HELLO\n".into())),
+                ..Default::default()
+            },
+            0,
+            DIR,
+            TIMEOUT,
+            Compare::CaseInsensitive,
+        )?;
+        assert!(case.expected.stdout_matches("hello\n"));
+        let case = build(toml::Case {
+            stdout: Some(toml::OneOrMany::One("[0-9]+\n".into())),
+            compare: Some("regex".into()),
+            ..Default::default()
+        })?;
+        assert!(case.expected.stdout_matches("123\n"));
+        let case = build(toml::Case {
+            stdout: Some(toml::OneOrMany::One("[0-9\n".into())),
+            compare: Some("regex".into()),
+            ..Default::default()
+        });
+        assert!(matches!(case, Err(QuestionError::InvalidCompare(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn args_or_cases() -> Result<(), QuestionError> {
+        let cases = build_cases(
+            Some(vec![toml::ArgSet::Args(vec![]), toml::ArgSet::Args(vec![])]),
+            None,
+            DIR,
+            TIMEOUT,
+            Compare::Exact,
+        )?
+        .unwrap();
+        assert_eq!(cases[1].name, "case 2");
+        assert!(build_cases(None, None, DIR, TIMEOUT, Compare::Exact)?.is_none());
+        let both = build_cases(Some(vec![]), Some(vec![]), DIR, TIMEOUT, Compare::Exact);
+        assert!(matches!(both, Err(QuestionError::InvalidCase(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+//! How the output of a submission is compared against what was expected
+//!
+//! A [`Compare`] mode is chosen with `compare` in the `[test]` table of a Question .toml file, or
+//! in a single case, which overrides it:
+//! * `exact` - The default, output must be identical
+//! * `trailing-whitespace` - Whitespace at the end of each line, and blank lines at the end of the
+//!   output, are ignored
+//! * `trailing-newline` - A single newline at the end of the output is ignored
+//! * `case-insensitive` - Upper and lower case letters are treated as the same
+//! * `regex` - The expected output is a regular expression the whole output must match
+//! * `unordered-lines` - The same lines must be output, in any order
+//! * `numeric` - Words that are numbers may differ by up to `tolerance` (`1e-6` unless given),
+//!   all other words must be identical
+
+use crate::question::QuestionError;
+use crate::utils::ProgramOutput;
+use regex::Regex;
+use std::fmt;
+
+/// Tolerance used by [`Compare::Numeric`] when the question doesn't give one
+pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Compare {
+    #[default]
+    Exact,
+    TrailingWhitespace,
+    TrailingNewline,
+    CaseInsensitive,
+    Regex,
+    UnorderedLines,
+    Numeric {
+        tolerance: f64,
+    },
+}
+
+impl Compare {
+    /// Build a [`Compare`] from the `compare` and `tolerance` keys of a Question .toml file, using
+    /// `default` if neither was given.
+    pub fn build_from_toml(
+        mode: Option<&str>,
+        tolerance: Option<f64>,
+        default: Self,
+    ) -> Result<Self, QuestionError> {
+        let compare = match mode {
+            None if tolerance.is_none() => return Ok(default),
+            None => default,
+            Some("exact") => Self::Exact,
+            Some("trailing-whitespace") => Self::TrailingWhitespace,
+            Some("trailing-newline") => Self::TrailingNewline,
+            Some("case-insensitive") => Self::CaseInsensitive,
+            Some("regex") => Self::Regex,
+            Some("unordered-lines") => Self::UnorderedLines,
+            Some("numeric") => Self::Numeric {
+                tolerance: DEFAULT_TOLERANCE,
+            },
+            Some(invalid) => return Err(QuestionError::InvalidCompare(invalid.into())),
+        };
+        match (compare, tolerance) {
+            (compare, None) => Ok(compare),
+            (Self::Numeric { .. }, Some(tolerance))
+                if tolerance.is_finite() && tolerance >= 0.0 =>
+            {
+                Ok(Self::Numeric { tolerance })
+            }
+            (Self::Numeric { .. }, Some(tolerance)) => Err(QuestionError::InvalidCompare(format!(
+                "tolerance must be a non-negative number: {}",
+                tolerance
+            ))),
+            (_, Some(_)) => Err(QuestionError::InvalidCompare(
+                "tolerance can only be used with 'numeric'".into(),
+            )),
+        }
+    }
+
+    /// Check that `expected` can be compared against, i.e. that it is a valid regular expression
+    /// when comparing with [`Compare::Regex`]
+    pub fn validate(&self, expected: &str) -> Result<(), QuestionError> {
+        match self {
+            Self::Regex => anchored_regex(expected)
+                .map(|_| ())
+                .map_err(|e| QuestionError::InvalidCompare(e.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Does `actual` match the `expected` text from a Question?
+    pub fn matches(&self, expected: &str, actual: &str) -> bool {
+        match self {
+            Self::Exact => expected == actual,
+            Self::TrailingWhitespace => {
+                without_trailing_whitespace(expected) == without_trailing_whitespace(actual)
+            }
+            Self::TrailingNewline => {
+                expected.strip_suffix('\n').unwrap_or(expected)
+                    == actual.strip_suffix('\n').unwrap_or(actual)
+            }
+            Self::CaseInsensitive => expected.to_lowercase() == actual.to_lowercase(),
+            Self::Regex => anchored_regex(expected).is_ok_and(|regex| regex.is_match(actual)),
+            Self::UnorderedLines => sorted_lines(expected) == sorted_lines(actual),
+            Self::Numeric { tolerance } => numbers_match(expected, actual, *tolerance),
+        }
+    }
+
+    /// Does the output of a submission match the output of a reference binary? The exit codes
+    /// must be identical. As the reference output is not a pattern, [`Compare::Regex`] compares
+    /// it exactly.
+    pub fn outputs_match(&self, expected: &ProgramOutput, actual: &ProgramOutput) -> bool {
+        let compare = match self {
+            Self::Regex => Self::Exact,
+            compare => *compare,
+        };
+        expected.code() == actual.code()
+            && expected.signal() == actual.signal()
+            && compare.matches(expected.stdout(), actual.stdout())
+            && compare.matches(expected.stderr(), actual.stderr())
+    }
+}
+
+impl fmt::Display for Compare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Exact => write!(f, "exact"),
+            Self::TrailingWhitespace => write!(f, "trailing-whitespace"),
+            Self::TrailingNewline => write!(f, "trailing-newline"),
+            Self::CaseInsensitive => write!(f, "case-insensitive"),
+            Self::Regex => write!(f, "regex"),
+            Self::UnorderedLines => write!(f, "unordered-lines"),
+            Self::Numeric { tolerance } => write!(f, "numeric, tolerance {}", tolerance),
+        }
+    }
+}
+
+fn anchored_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{})$", pattern))
+}
+
+fn without_trailing_whitespace(text: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines
+}
+
+fn sorted_lines(text: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    lines.sort_unstable();
+    lines
+}
+
+fn numbers_match(expected: &str, actual: &str, tolerance: f64) -> bool {
+    let expected: Vec<&str> = expected.split_whitespace().collect();
+    let actual: Vec<&str> = actual.split_whitespace().collect();
+    expected.len() == actual.len()
+        && expected
+            .iter()
+            .zip(actual.iter())
+            .all(
+                |(expected, actual)| match (expected.parse::<f64>(), actual.parse::<f64>()) {
+                    (Ok(expected), Ok(actual)) => (expected - actual).abs() <= tolerance,
+                    _ => expected == actual,
+                },
+            )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_modes() {
+        assert!(Compare::Exact.matches("a\n", "a\n"));
+        assert!(!Compare::Exact.matches("a\n", "a"));
+        assert!(Compare::TrailingWhitespace.matches("a\nb\n", "a  \nb\t\n\n"));
+        assert!(!Compare::TrailingWhitespace.matches("a b\n", "a  b\n"));
+        assert!(Compare::TrailingNewline.matches("a\n", "a"));
+        assert!(!Compare::TrailingNewline.matches("a\n", "a\n\n"));
+        assert!(Compare::CaseInsensitive.matches("Hello\n", "hELLO\n"));
+        assert!(Compare::Regex.matches("[0-9]+\\n", "42\n"));
+        assert!(!Compare::Regex.matches("[0-9]+", "42\nextra"));
+        assert!(Compare::UnorderedLines.matches("a\nb\nc\n", "c\na\nb\n"));
+        assert!(!Compare::UnorderedLines.matches("a\nb\n", "a\na\n"));
+        let numeric = Compare::Numeric { tolerance: 0.01 };
+        assert!(numeric.matches("pi is 3.14159\n", "pi is 3.14\n"));
+        assert!(!numeric.matches("pi is 3.14159\n", "pi is 3.1\n"));
+        assert!(!numeric.matches("pi is 3.14\n", "PI is 3.14\n"));
+    }
+
+    #[test]
+    fn compare_from_toml() -> Result<(), QuestionError> {
+        let default = Compare::CaseInsensitive;
+        assert_eq!(Compare::build_from_toml(None, None, default)?, default);
+        assert_eq!(
+            Compare::build_from_toml(Some("regex"), None, default)?,
+            Compare::Regex
+        );
+        assert_eq!(
+            Compare::build_from_toml(Some("numeric"), None, default)?,
+            Compare::Numeric {
+                tolerance: DEFAULT_TOLERANCE
+            }
+        );
+        assert_eq!(
+            Compare::build_from_toml(None, Some(0.5), Compare::Numeric { tolerance: 0.1 })?,
+            Compare::Numeric { tolerance: 0.5 }
+        );
+        for (mode, tolerance) in [
+            (Some("fuzzy"), None),
+            (Some("exact"), Some(0.1)),
+            (Some("numeric"), Some(-1.0)),
+        ] {
+            assert!(matches!(
+                Compare::build_from_toml(mode, tolerance, default),
+                Err(QuestionError::InvalidCompare(_))
+            ));
+        }
+        assert!(Compare::Regex.validate("(unclosed").is_err());
+        Ok(())
+    }
+}
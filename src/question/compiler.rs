@@ -2,8 +2,34 @@ use crate::question::test::TestError;
 use crate::question::QuestionError;
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// Variables defined by the C library, which a submission may use whatever functions it is
+/// allowed to call
+///
+/// An undefined symbol in an object file has no type, so `nm` cannot tell a variable from a
+/// function, and only the variables named here are left out. Any other variable a submission uses
+/// is checked as if it were a function, and must be listed in `allowed_functions` to be used.
+const LIBC_VARIABLES: [&str; 15] = [
+    "stdin",
+    "stdout",
+    "stderr",
+    "environ",
+    "errno",
+    "optarg",
+    "optind",
+    "opterr",
+    "optopt",
+    "timezone",
+    "daylight",
+    "tzname",
+    "signgam",
+    "program_invocation_name",
+    "program_invocation_short_name",
+];
+
 #[derive(Debug)]
 pub enum CompileResult {
     Ok(String),
@@ -11,6 +37,63 @@ pub enum CompileResult {
 }
 
 #[derive(Debug)]
+pub enum BuildResult {
+    Ok(Build),
+    Err(TestError),
+}
+
+/// A temporary directory, holding the binary or library that was built in it
+#[derive(Debug)]
+pub struct Build {
+    dir: PathBuf,
+    artifact: PathBuf,
+}
+
+impl Build {
+    /// Create an empty temporary directory, in which `artifact` will be built
+    pub fn new(artifact: &str) -> Result<Self, QuestionError> {
+        let dir = temp_dir("build")?;
+        Ok(Self {
+            artifact: dir.join(artifact),
+            dir,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the binary or library that was built
+    pub fn artifact(&self) -> String {
+        self.artifact.display().to_string()
+    }
+
+    /// Remove the directory, and everything built in it
+    pub fn remove(self) -> Result<(), QuestionError> {
+        std::fs::remove_dir_all(&self.dir)?;
+        Ok(())
+    }
+
+    /// Keep the build if the checks made on it found nothing wrong, and remove it otherwise
+    pub fn finish(
+        self,
+        checked: Result<Option<TestError>, QuestionError>,
+    ) -> Result<BuildResult, QuestionError> {
+        match checked {
+            Ok(None) => Ok(BuildResult::Ok(self)),
+            Ok(Some(error)) => {
+                self.remove()?;
+                Ok(BuildResult::Err(error))
+            }
+            Err(error) => {
+                self.remove()?;
+                Err(error)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Compiler<'a> {
     compiler: &'a str,
     sources: Vec<String>,
@@ -34,6 +117,13 @@ impl<'a> Compiler<'a> {
         self.flags.push(flag);
     }
 
+    /// A copy of this [`Compiler`], which also passes `flags`
+    pub fn with_flags(&self, flags: &[&'a str]) -> Self {
+        let mut compiler = self.clone();
+        compiler.flags.extend_from_slice(flags);
+        compiler
+    }
+
     pub fn compile(&self) -> Result<CompileResult, QuestionError> {
         let binary_name: String = thread_rng()
             .sample_iter(&Alphanumeric)
@@ -59,6 +149,132 @@ impl<'a> Compiler<'a> {
             Ok(CompileResult::Ok(binary_name))
         }
     }
+
+    /// The functions called by `sources` that none of them define, found by compiling each one
+    /// to an object file with the same flags, and listing its symbols with `nm`.
+    ///
+    /// Builtins are disabled, so that e.g. a call to `printf` is not replaced by `puts`. Symbols
+    /// starting with `_` are reserved for the compiler and C library, and are left out, as are
+    /// the C library's variables, which `nm` lists the same way as functions.
+    pub fn undefined_functions(&self, sources: &[String]) -> Result<Vec<String>, QuestionError> {
+        let dir = temp_dir("objects")?;
+        let result = self.list_symbols(sources, &dir);
+        std::fs::remove_dir_all(&dir)?;
+        let symbols = result?;
+
+        let mut defined = BTreeSet::new();
+        let mut undefined = BTreeSet::new();
+        // `nm -P` prints "name type [value size]" for each symbol
+        for line in symbols.lines() {
+            let mut fields = line.split_whitespace();
+            match (fields.next(), fields.next()) {
+                (Some(name), Some("U")) => undefined.insert(name),
+                (Some(name), Some(_)) => defined.insert(name),
+                _ => false,
+            };
+        }
+        Ok(undefined
+            .difference(&defined)
+            .filter(|name| !name.starts_with('_') && !LIBC_VARIABLES.contains(name))
+            .map(|name| name.to_string())
+            .collect())
+    }
+
+    fn list_symbols(&self, sources: &[String], dir: &Path) -> Result<String, QuestionError> {
+        let objects = self
+            .compile_objects(sources, dir, &["-fno-builtin"])?
+            .map_err(QuestionError::SymbolCheck)?;
+        nm(&["-P", "-g"], &objects)
+    }
+
+    /// Compile each of the sources to an object file, and archive them into the static library
+    /// at `library` with `ar`. The object files are left beside it.
+    pub fn compile_library(&self, library: &Path) -> Result<CompileResult, QuestionError> {
+        let dir = library.parent().unwrap_or(Path::new("."));
+        let objects = match self.compile_objects(&self.sources, dir, &[])? {
+            Ok(objects) => objects,
+            Err(stderr) => return Ok(CompileResult::Err(TestError::DoesNotCompile(stderr))),
+        };
+        let output = Command::new("ar")
+            .arg("rcs")
+            .arg(library)
+            .args(&objects)
+            .output()?;
+        if output.status.success() {
+            Ok(CompileResult::Ok(library.display().to_string()))
+        } else {
+            Ok(CompileResult::Err(TestError::DoesNotCompile(
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            )))
+        }
+    }
+
+    /// Compile each of `sources` to an object file in `dir`, passing `extra_flags` as well as the
+    /// usual ones. If one does not compile, its errors are returned instead.
+    fn compile_objects(
+        &self,
+        sources: &[String],
+        dir: &Path,
+        extra_flags: &[&str],
+    ) -> Result<Result<Vec<PathBuf>, String>, QuestionError> {
+        let mut objects = Vec::new();
+        for (index, source) in sources.iter().enumerate() {
+            let object = dir.join(format!("{}.o", index));
+            let output = Command::new(self.compiler)
+                .arg("-c")
+                .args(extra_flags)
+                .arg(source)
+                .arg("-o")
+                .arg(&object)
+                .args(self.flags.iter())
+                .output()?;
+            if !output.status.success() {
+                return Ok(Err(String::from_utf8_lossy(&output.stderr).into_owned()));
+            }
+            objects.push(object);
+        }
+        Ok(Ok(objects))
+    }
+}
+
+/// The global symbols, functions and variables alike, that the static library at `library`
+/// defines
+pub fn exported_symbols(library: &Path) -> Result<Vec<String>, QuestionError> {
+    let symbols = nm(&["-P", "-g", "--defined-only"], &[library])?;
+    // Each member of the archive starts with a "library[member]:" line, with no type
+    let exported: BTreeSet<&str> = symbols
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            match (fields.next(), fields.next()) {
+                (Some(name), Some(_)) => Some(name),
+                _ => None,
+            }
+        })
+        .collect();
+    Ok(exported.into_iter().map(|name| name.to_string()).collect())
+}
+
+fn nm<P: AsRef<std::ffi::OsStr>>(args: &[&str], files: &[P]) -> Result<String, QuestionError> {
+    let output = Command::new("nm").args(args).args(files).output()?;
+    if !output.status.success() {
+        return Err(QuestionError::SymbolCheck(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Create an empty directory for examtrainer to work in, named after its `purpose`
+pub fn temp_dir(purpose: &str) -> Result<PathBuf, QuestionError> {
+    let name: String = thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(10)
+        .map(char::from)
+        .collect();
+    let dir = std::env::temp_dir().join(format!("examtrainer-{}-{}", purpose, name));
+    std::fs::create_dir(&dir)?;
+    Ok(dir)
 }
 
 pub fn remove_binary(binary: &str) -> Result<std::process::Output, QuestionError> {
@@ -75,12 +291,12 @@ mod tests {
     use crate::utils::ProgramOutput;
     #[test]
     fn compile_test() -> Result<(), QuestionError> {
-        let submit_sources = vec!["tst/resources/rendu_test/hello_world/hello_world.c"];
-        let test_sources = vec!["tst/resources/questions/hello_world/main.c"];
-        let submit_flags = vec![];
-        let test_flags = vec!["-Wall", "-Wextra", "-Werror"];
+        let submit_sources = ["tst/resources/rendu_test/hello_world/hello_world.c"];
+        let test_sources = ["tst/resources/questions/hello_world/main.c"];
+        let submit_flags: [&str; 0] = [];
+        let test_flags = ["-Wall", "-Wextra", "-Werror"];
         let test_compiler = String::from("gcc");
-        let mut compiler = Compiler::new(&&test_compiler);
+        let mut compiler = Compiler::new(&test_compiler);
         for flag in test_flags.iter().chain(submit_flags.iter()) {
             compiler.add_flag(flag);
         }
@@ -102,4 +318,55 @@ mod tests {
         remove_binary(&binary)?;
         Ok(())
     }
+
+    #[test]
+    fn compile_library() -> Result<(), QuestionError> {
+        let mut compiler = Compiler::new("gcc");
+        for source in ["ft_strlen.c", "ft_strcpy.c"] {
+            compiler.add_source(format!("tst/resources/rendu_test/libft_str/{}", source));
+        }
+        let build = Build::new("libft.a")?;
+        let compile_result = compiler.compile_library(Path::new(&build.artifact()));
+        let symbols = exported_symbols(Path::new(&build.artifact()));
+        build.remove()?;
+        assert!(matches!(compile_result?, CompileResult::Ok(_)));
+        // The static helper of ft_strcpy is not exported
+        assert_eq!(symbols?, ["ft_strcpy", "ft_strlen"]);
+        Ok(())
+    }
+
+    #[test]
+    fn undefined_functions() -> Result<(), QuestionError> {
+        let sources = [
+            "tst/resources/rendu_test/hello_world/hello_world.c".to_string(),
+            "tst/resources/questions/hello_world/main.c".to_string(),
+        ];
+        let mut compiler = Compiler::new("gcc");
+        compiler.add_flag("-Wall");
+        // hello_world is defined by one source and called by the other
+        assert_eq!(compiler.undefined_functions(&sources)?, ["write"]);
+        assert_eq!(
+            compiler.undefined_functions(&sources[1..])?,
+            ["hello_world"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn libc_variables_are_not_functions() -> Result<(), QuestionError> {
+        let dir = temp_dir("sources")?;
+        let source = dir.join("print_error.c");
+        std::fs::write(
+            &source,
+            "#include <stdio.h>\n#include <unistd.h>\n\n\
+             int main(int argc, char **argv)\n{\n\
+             \tif (getopt(argc, argv, \"v\") == 'v')\n\
+             \t\tfputs(optarg, stderr);\n\
+             \treturn (0);\n}\n",
+        )?;
+        let result = Compiler::new("gcc").undefined_functions(&[source.display().to_string()]);
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(result?, ["fputs", "getopt"]);
+        Ok(())
+    }
 }
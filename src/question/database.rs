@@ -15,28 +15,39 @@ pub struct QuestionDB {
 impl QuestionDB {
     /// Generate a database ([`HashMap`]) of [`Question`]'s.
     ///
-    /// [`QuestionDB::new`] iterates over all directories within the `question_directory` specified
-    /// in [`Config`], and attempts to generate a [`Question`] from each of them. If a [`Question`]
-    /// cannot be created for any reason, then a Warning is displayed on the screen, along with the
-    /// reason for failure (a [`QuestionError`]).
+    /// [`QuestionDB::new`] iterates over all directories within each `question_directory`
+    /// specified in [`Config`], and attempts to generate a [`Question`] from each of them. If a
+    /// [`Question`] cannot be created for any reason, then a Warning is displayed on the screen,
+    /// along with the reason for failure (a [`QuestionError`]).
+    ///
+    /// Question directories are searched in the order they are listed in [`Config`]. If two
+    /// questions have the same name, the first one found is kept, and a Warning naming the
+    /// location of both is displayed.
     pub fn new(config: &Config) -> Result<Self, Error> {
-        let question_dirs = std::fs::read_dir(config.question_dir())?.filter(|entry| {
-            if let Ok(file) = entry {
-                file.path().is_dir()
-            } else {
-                false
-            }
-        });
         let mut questions = HashMap::new();
-        for dir in question_dirs.into_iter().flatten() {
-            match Question::build_from_dir_entry(config, &dir) {
-                Ok(question) => insert_new_question(&mut questions, question),
-                Err(e) => print_question_error(&dir, e),
+        for question_dir in config.question_dirs() {
+            let mut question_dirs: Vec<DirEntry> = std::fs::read_dir(question_dir)?
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .collect();
+            question_dirs.sort_by_key(|entry| entry.path());
+            for dir in question_dirs.iter() {
+                match Question::build_from_dir_entry(config, dir) {
+                    Ok(question) => insert_new_question(&mut questions, question),
+                    Err(e) => print_question_error(dir, e),
+                }
             }
         }
         Ok(Self { questions })
     }
 
+    /// All questions in the database, sorted by name
+    pub fn questions(&self) -> Vec<&Question> {
+        let mut questions: Vec<&Question> = self.questions.values().collect();
+        questions.sort_by_key(|question| question.name());
+        questions
+    }
+
     pub fn get_question_by_name(&self, name: &str) -> Option<&Question> {
         self.questions.get(name)
     }
@@ -62,12 +73,14 @@ impl fmt::Display for QuestionDB {
 }
 
 fn insert_new_question(questions: &mut HashMap<String, Question>, question: Question) {
-    if questions.get(question.name()).is_some() {
+    if let Some(existing) = questions.get(question.name()) {
         eprintln!(
             "{}",
             format!(
-                "Warning: The question {} appeared twice, second instance was ignored",
-                question.name()
+                "Warning: The question {} in {} was ignored, as it is already defined in {}",
+                question.name(),
+                question.directories().question_directory,
+                existing.directories().question_directory
             )
             .yellow()
         );
@@ -108,7 +121,22 @@ mod tests {
         let question = question_database.get_question_by_name("hello_world");
         assert!(question.is_some());
         let question = question.unwrap();
-        assert_eq!(question.difficulty(), 2);
+        assert_eq!(question.difficulty(), Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn question_directory_precedence() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config3.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("hello_world");
+        assert!(question.is_some());
+        let question = question.unwrap();
+        assert!(question
+            .directories()
+            .question_directory
+            .ends_with("questions_extra/hello_world"));
+        assert!(question_database.get_question_by_name("aff_a").is_some());
         Ok(())
     }
 
@@ -14,6 +14,15 @@ pub enum QuestionError {
     NoStderr,
     MissingKey(MissingKeys),
     InvalidFramework(String),
+    UnknownToolchain(String),
+    UnknownInterpreter(String),
+    InvalidTimeout(f64),
+    InvalidCase(String),
+    InvalidGenerator(String),
+    InvalidCompare(String),
+    InvalidPrototype(String),
+    SymbolCheck(String),
+    TestTimeout(String),
     MultipleConfigs,
     NoConfig,
     DuplicateQuestion(String),
@@ -37,6 +46,33 @@ impl fmt::Display for QuestionError {
             Self::NoStderr => write!(f, "Expected Stderr file does not exist"),
             Self::MissingKey(e) => write!(f, "Missing key: {}", e),
             Self::InvalidFramework(frame) => write!(f, "Invalid framework: {}", frame),
+            Self::UnknownToolchain(name) => {
+                write!(f, "Toolchain '{}' is not defined in Config", name)
+            }
+            Self::UnknownInterpreter(name) => {
+                write!(f, "Interpreter '{}' is not defined in Config", name)
+            }
+            Self::InvalidTimeout(timeout) => {
+                write!(
+                    f,
+                    "Timeout must be a positive number of seconds: {}",
+                    timeout
+                )
+            }
+            Self::InvalidCase(reason) => write!(f, "Invalid test case: {}", reason),
+            Self::InvalidGenerator(reason) => write!(f, "Invalid generator: {}", reason),
+            Self::InvalidCompare(reason) => write!(f, "Invalid compare mode: {}", reason),
+            Self::InvalidPrototype(reason) => write!(f, "Invalid prototype: {}", reason),
+            Self::SymbolCheck(reason) => write!(
+                f,
+                "Could not check the functions used by the submission: {}",
+                reason
+            ),
+            Self::TestTimeout(case) => write!(
+                f,
+                "The question's own program timed out in {}, so the question is invalid",
+                case
+            ),
             Self::MultipleConfigs => {
                 write!(f, "Multiple .toml files were found in Question directory")
             }
@@ -84,8 +120,13 @@ pub enum MissingKeys {
     UnitTest,
     Sources,
     CompiledTogether,
+    Function,
+    Filesystem,
     SubExec,
     SubSources,
+    SubScript,
+    SubMakefile,
+    SubLibrary,
 }
 
 impl fmt::Display for MissingKeys {
@@ -106,7 +147,17 @@ impl fmt::Display for MissingKeys {
             Self::CompiledTogether => write!(
                 f,
                 "'expected-output' type question must contain the following keys:
-- sources\n- compiler\n- expected_stdout\n- expected_stderr\n- args",
+- sources\n- compiler\n- expected_stdout\n- expected_stderr\n- args
+'cases' may be given instead of args, expected_stdout and expected_stderr",
+            ),
+            Self::Function => write!(
+                f,
+                "'function' type question must contain the following keys:
+- compiler\n- prototype\n- calls",
+            ),
+            Self::Filesystem => write!(
+                f,
+                "'filesystem' type question must contain the following key:\n- binary",
             ),
             Self::SubExec => write!(
                 f,
@@ -116,6 +167,18 @@ impl fmt::Display for MissingKeys {
                 f,
                 "'sources' type submission must contain the following keys:\n- sources\n- compiler",
             ),
+            Self::SubScript => write!(
+                f,
+                "'script' type submission must contain the following keys:\n- script\n- interpreter",
+            ),
+            Self::SubMakefile => write!(
+                f,
+                "'makefile' type submission must contain one of the following keys:\n- binary\n- library",
+            ),
+            Self::SubLibrary => write!(
+                f,
+                "'library' type submission must contain the following keys:\n- sources\n- compiler\n- library",
+            ),
         }
     }
 }
@@ -0,0 +1,265 @@
+//! Generated C harnesses for 'function' type tests
+//!
+//! A 'function' test declares the prototype of the function a user should submit, and the calls
+//! to make to it:
+//! ```toml
+//! [test]
+//! test_type = "function"
+//! prototype = "size_t ft_strlen(const char *str)"
+//!
+//! [[test.calls]]
+//! args = ['"hello"']
+//! returns = 5
+//! ```
+//! Arguments and return values are C expressions. A harness is generated from them, which makes
+//! the call chosen by its first argument, and exits with a non-zero code after describing the
+//! difference on stderr if the return value is not as expected.
+
+use crate::question::QuestionError;
+use std::fmt::Write;
+
+/// Headers the harness always includes
+const HEADERS: [&str; 5] = ["stdbool.h", "stddef.h", "stdio.h", "stdlib.h", "string.h"];
+
+/// The kinds of return value a harness knows how to compare
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReturnType {
+    Void,
+    Signed,
+    Unsigned,
+    Float,
+    String,
+}
+
+/// A parsed function prototype, e.g. `char *ft_strdup(const char *src)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prototype {
+    declaration: String,
+    name: String,
+    return_type: String,
+    kind: ReturnType,
+}
+
+impl Prototype {
+    pub fn parse(declaration: &str) -> Result<Self, QuestionError> {
+        let declaration = declaration.trim().trim_end_matches(';').trim();
+        let invalid =
+            |reason: &str| QuestionError::InvalidPrototype(format!("'{}' {}", declaration, reason));
+        let (head, _) = declaration
+            .split_once('(')
+            .filter(|_| declaration.ends_with(')'))
+            .ok_or_else(|| invalid("is not a function prototype"))?;
+        let head = head.trim_end();
+        let name_start = head
+            .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .map_or(0, |index| index + 1);
+        let name = &head[name_start..];
+        let return_type = head[..name_start].trim();
+        if name.is_empty() || return_type.is_empty() {
+            return Err(invalid("must have a return type and a name"));
+        }
+        let kind = Self::classify(return_type)
+            .ok_or_else(|| invalid("has a return type that cannot be compared"))?;
+        Ok(Self {
+            declaration: declaration.to_string(),
+            name: name.to_string(),
+            return_type: return_type.to_string(),
+            kind,
+        })
+    }
+
+    fn classify(return_type: &str) -> Option<ReturnType> {
+        let pointers = return_type.matches('*').count();
+        let words: Vec<&str> = return_type
+            .split(|c: char| c.is_whitespace() || c == '*')
+            .filter(|word| !word.is_empty() && *word != "const")
+            .collect();
+        match (pointers, &words[..]) {
+            (0, ["void"]) => Some(ReturnType::Void),
+            (0, ["float"] | ["double"] | ["long", "double"]) => Some(ReturnType::Float),
+            (0, ["size_t"] | ["bool"] | ["_Bool"]) => Some(ReturnType::Unsigned),
+            (0, ["unsigned", ..]) => Some(ReturnType::Unsigned),
+            (0, ["ssize_t"]) => Some(ReturnType::Signed),
+            (0, words)
+                if words
+                    .iter()
+                    .all(|word| ["signed", "char", "short", "int", "long"].contains(word)) =>
+            {
+                Some(ReturnType::Signed)
+            }
+            (1, ["char"] | ["signed", "char"] | ["unsigned", "char"]) => Some(ReturnType::String),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> ReturnType {
+        self.kind
+    }
+}
+
+/// A single call to the function under test
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub args: Vec<String>,
+    /// The expected return value, unchecked if [`None`]
+    pub returns: Option<String>,
+}
+
+impl Call {
+    /// The call as it is written in C, e.g. `ft_strlen("hello")`
+    pub fn expression(&self, prototype: &Prototype) -> String {
+        format!("{}({})", prototype.name, self.args.join(", "))
+    }
+}
+
+/// Generate the source of a harness for `calls`, including `includes` as well as the standard
+/// headers. Floating point return values may differ from those expected by up to `tolerance`.
+pub fn generate_harness(
+    prototype: &Prototype,
+    calls: &[Call],
+    includes: &[String],
+    tolerance: f64,
+) -> String {
+    let mut source = String::new();
+    for header in HEADERS
+        .iter()
+        .copied()
+        .chain(includes.iter().map(|s| &s[..]))
+    {
+        let _ = writeln!(source, "#include <{}>", header);
+    }
+    let _ = write!(
+        source,
+        "\n{};\n\nint main(int argc, char **argv) {{\n    if (argc < 2)\n        return 2;\n    \
+         switch (atoi(argv[1])) {{\n",
+        prototype.declaration
+    );
+    for (index, call) in calls.iter().enumerate() {
+        let _ = writeln!(source, "    case {}: {{", index);
+        source += &check_call(prototype, call, tolerance);
+        source += "        return 0;\n    }\n";
+    }
+    source += "    }\n    return 2;\n}\n";
+    source
+}
+
+fn check_call(prototype: &Prototype, call: &Call, tolerance: f64) -> String {
+    let expression = call.expression(prototype);
+    let expected = match (&call.returns, prototype.kind) {
+        (Some(expected), kind) if kind != ReturnType::Void => expected,
+        _ => return format!("        {};\n", expression),
+    };
+    let (declare, differs, format, cast) = match prototype.kind {
+        ReturnType::Signed => (
+            prototype.return_type.clone(),
+            "actual != expected".to_string(),
+            "%lld",
+            "(long long)",
+        ),
+        ReturnType::Unsigned => (
+            prototype.return_type.clone(),
+            "actual != expected".to_string(),
+            "%llu",
+            "(unsigned long long)",
+        ),
+        ReturnType::Float => (
+            prototype.return_type.clone(),
+            format!(
+                "!(actual - expected <= {0:e} && expected - actual <= {0:e})",
+                tolerance
+            ),
+            "%.17g",
+            "(double)",
+        ),
+        ReturnType::String | ReturnType::Void => (
+            "const char *".to_string(),
+            "(actual == NULL || expected == NULL) ? actual != expected : strcmp(actual, expected)"
+                .to_string(),
+            "\\\"%s\\\"",
+            "",
+        ),
+    };
+    let describe = |value: &str| match prototype.kind {
+        ReturnType::String => format!("{0} ? {0} : \"(null)\"", value),
+        _ => format!("{}{}", cast, value),
+    };
+    format!(
+        "        {declare} expected = ({expected});\n        \
+         {declare} actual = {expression};\n        \
+         if ({differs}) {{\n            \
+         fprintf(stderr, \"expected {format}, got {format}\\n\", {expected_value}, {actual_value});\n            \
+         return 1;\n        }}\n",
+        declare = declare,
+        expected = expected,
+        expression = expression,
+        differs = differs,
+        format = format,
+        expected_value = describe("expected"),
+        actual_value = describe("actual"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_prototypes() -> Result<(), QuestionError> {
+        let prototype = Prototype::parse("size_t ft_strlen(const char *str);")?;
+        assert_eq!(prototype.name(), "ft_strlen");
+        assert_eq!(prototype.kind(), ReturnType::Unsigned);
+        let prototype = Prototype::parse("char\t*ft_strdup(char *src)")?;
+        assert_eq!(prototype.name(), "ft_strdup");
+        assert_eq!(prototype.return_type, "char\t*");
+        assert_eq!(prototype.kind(), ReturnType::String);
+        for (declaration, kind) in [
+            ("int ft_atoi(const char *str)", ReturnType::Signed),
+            (
+                "unsigned char reverse_bits(unsigned char octet)",
+                ReturnType::Unsigned,
+            ),
+            ("long long ft_abs(long long n)", ReturnType::Signed),
+            ("double average(int *nums, int n)", ReturnType::Float),
+            ("void ft_putstr(char *str)", ReturnType::Void),
+        ] {
+            assert_eq!(Prototype::parse(declaration)?.kind(), kind);
+        }
+        for invalid in [
+            "ft_strlen",
+            "int (char *str)",
+            "int *ft_range(int min, int max)",
+        ] {
+            assert!(matches!(
+                Prototype::parse(invalid),
+                Err(QuestionError::InvalidPrototype(_))
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn harness_source() -> Result<(), QuestionError> {
+        let prototype = Prototype::parse("int ft_atoi(const char *str)")?;
+        let calls = vec![
+            Call {
+                args: vec!["\"42\"".into()],
+                returns: Some("42".into()),
+            },
+            Call {
+                args: vec!["\"\"".into()],
+                returns: None,
+            },
+        ];
+        let source = generate_harness(&prototype, &calls, &["limits.h".into()], 0.0);
+        assert!(source.contains("#include <limits.h>\n"));
+        assert!(source.contains("int ft_atoi(const char *str);\n"));
+        assert!(source.contains("    case 0: {\n        int expected = (42);\n"));
+        assert!(source.contains("        int actual = ft_atoi(\"42\");\n"));
+        assert!(source.contains("    case 1: {\n        ft_atoi(\"\");\n        return 0;\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+//! Random cases for 'executable' and 'sources' tests
+//!
+//! A fixed `args` list can be passed by hardcoding its outputs. A `[test.generator]` table adds
+//! `count` cases with random arguments each time the submission is graded, which are run against
+//! both the submission and the reference like any other case:
+//! ```toml
+//! [test.generator]
+//! kind = "ints"
+//! min = -100
+//! max = 100
+//! min_args = 2
+//! max_args = 2
+//! ```
+//!
+//! The built-in kinds are `ints` in a range, `strings` of characters from a `charset`, and
+//! `words`, where each argument is a sentence of words picked from a list. A question can instead
+//! give a `program` in its directory, which is run with the seed as its only argument, and prints
+//! one argument per line. Each case has its own seed, which is recorded in the trace, so that a
+//! failing case can be generated again.
+
+use crate::question::case::{Case, Expected};
+use crate::question::compare::Compare;
+use crate::question::toml;
+use crate::question::QuestionError;
+use crate::question::{run_binary, BinaryResult};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+/// Cases generated each time a submission is graded, when `count` is not given
+const DEFAULT_COUNT: usize = 10;
+
+const DEFAULT_INTS: RangeInclusive<i64> = -1000..=1000;
+
+/// Length of generated strings in characters, or of sentences in words
+const DEFAULT_LENGTHS: RangeInclusive<usize> = 1..=20;
+
+const DEFAULT_CHARSET: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+#[derive(Debug)]
+enum Kind {
+    /// A program in the question directory, run with the seed
+    Program(String),
+    Ints(RangeInclusive<i64>),
+    Strings {
+        lengths: RangeInclusive<usize>,
+        charset: Vec<char>,
+    },
+    Words {
+        lengths: RangeInclusive<usize>,
+        words: Vec<String>,
+    },
+}
+
+#[derive(Debug)]
+pub struct Generator {
+    kind: Kind,
+    count: usize,
+    /// Number of arguments of each case, for the built-in kinds
+    args: RangeInclusive<usize>,
+    timeout: Duration,
+    compare: Compare,
+}
+
+impl Generator {
+    /// Build a [`Generator`] from a `[test.generator]` table. Its cases run for `timeout`, and
+    /// their output is compared with the reference's using `compare`. A `program` is looked for
+    /// in `dir_path`, the question directory.
+    pub fn build_from_toml(
+        toml: toml::Generator,
+        dir_path: &str,
+        timeout: Duration,
+        compare: Compare,
+    ) -> Result<Self, QuestionError> {
+        let lengths = range(
+            toml.min_length,
+            toml.max_length,
+            DEFAULT_LENGTHS,
+            "min_length",
+            "max_length",
+        )?;
+        let kind = match (toml.program, toml.kind.as_deref()) {
+            (Some(program), None) => Kind::Program(format!("{}/{}", dir_path, program)),
+            (None, Some("ints")) => {
+                Kind::Ints(range(toml.min, toml.max, DEFAULT_INTS, "min", "max")?)
+            }
+            (None, Some("strings")) => {
+                let charset: Vec<char> = toml
+                    .charset
+                    .as_deref()
+                    .unwrap_or(DEFAULT_CHARSET)
+                    .chars()
+                    .collect();
+                if charset.is_empty() {
+                    return Err(invalid("'charset' cannot be empty"));
+                }
+                Kind::Strings { lengths, charset }
+            }
+            (None, Some("words")) => match toml.words {
+                Some(words) if !words.is_empty() => Kind::Words { lengths, words },
+                _ => return Err(invalid("'words' kind needs a list of 'words'")),
+            },
+            (None, Some(kind)) => return Err(invalid(&format!("unknown kind '{}'", kind))),
+            _ => return Err(invalid("exactly one of 'program' and 'kind' must be given")),
+        };
+        Ok(Self {
+            kind,
+            count: toml.count.unwrap_or(DEFAULT_COUNT),
+            args: range(toml.min_args, toml.max_args, 1..=1, "min_args", "max_args")?,
+            timeout,
+            compare,
+        })
+    }
+
+    /// Generate `count` cases, each from a new random seed
+    pub fn cases(&self) -> Result<Vec<Case>, QuestionError> {
+        let mut rng = thread_rng();
+        (0..self.count)
+            .map(|index| self.case(index, rng.gen()))
+            .collect()
+    }
+
+    /// The `index`th case, with arguments generated from `seed`
+    fn case(&self, index: usize, seed: u64) -> Result<Case, QuestionError> {
+        Ok(Case {
+            name: format!("random case {}", index + 1),
+            args: self.args(seed)?,
+            seed: Some(seed),
+            expected: Expected {
+                compare: self.compare,
+                ..Default::default()
+            },
+            ..Case::empty(self.timeout)
+        })
+    }
+
+    /// The arguments generated from `seed`, which are always the same for the same seed
+    pub fn args(&self, seed: u64) -> Result<Vec<String>, QuestionError> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let count = rng.gen_range(self.args.clone());
+        let args = match &self.kind {
+            Kind::Program(program) => return run_program(program, seed, self.timeout),
+            Kind::Ints(ints) => (0..count)
+                .map(|_| rng.gen_range(ints.clone()).to_string())
+                .collect(),
+            Kind::Strings { lengths, charset } => (0..count)
+                .map(|_| {
+                    let length = rng.gen_range(lengths.clone());
+                    (0..length)
+                        .map(|_| *charset.choose(&mut rng).unwrap())
+                        .collect()
+                })
+                .collect(),
+            Kind::Words { lengths, words } => (0..count)
+                .map(|_| {
+                    let length = rng.gen_range(lengths.clone());
+                    let sentence: Vec<&str> = (0..length)
+                        .map(|_| &words.choose(&mut rng).unwrap()[..])
+                        .collect();
+                    sentence.join(" ")
+                })
+                .collect(),
+        };
+        Ok(args)
+    }
+}
+
+fn run_program(program: &str, seed: u64, timeout: Duration) -> Result<Vec<String>, QuestionError> {
+    let case = Case {
+        args: vec![seed.to_string()],
+        ..Case::empty(timeout)
+    };
+    let output = match run_binary(program, &case)? {
+        BinaryResult::Output(output) => output,
+        BinaryResult::Timeout => {
+            return Err(invalid(&format!(
+                "{} timed out with seed {}",
+                program, seed
+            )))
+        }
+    };
+    if output.code() != 0 {
+        return Err(invalid(&format!(
+            "{} failed with seed {}: {}",
+            program,
+            seed,
+            output.stderr()
+        )));
+    }
+    Ok(output.stdout().lines().map(String::from).collect())
+}
+
+/// The range from `min` to `max`, each taking its value from `default` if it is not given
+fn range<T: PartialOrd + Copy>(
+    min: Option<T>,
+    max: Option<T>,
+    default: RangeInclusive<T>,
+    min_key: &str,
+    max_key: &str,
+) -> Result<RangeInclusive<T>, QuestionError> {
+    let min = min.unwrap_or(*default.start());
+    let max = max.unwrap_or(*default.end());
+    if min > max {
+        Err(invalid(&format!(
+            "'{}' cannot be greater than '{}'",
+            min_key, max_key
+        )))
+    } else {
+        Ok(min..=max)
+    }
+}
+
+fn invalid(reason: &str) -> QuestionError {
+    QuestionError::InvalidGenerator(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn build(toml: toml::Generator) -> Result<Generator, QuestionError> {
+        Generator::build_from_toml(toml, "tst/resources/questions/add", TIMEOUT, Compare::Exact)
+    }
+
+    #[test]
+    fn generated_args() -> Result<(), QuestionError> {
+        let generator = build(toml::Generator {
+            kind: Some("ints".into()),
+            min: Some(-5),
+            max: Some(5),
+            min_args: Some(2),
+            max_args: Some(3),
+            ..Default::default()
+        })?;
+        for seed in 0..20 {
+            let args = generator.args(seed)?;
+            assert_eq!(args, generator.args(seed)?);
+            assert!((2..=3).contains(&args.len()));
+            for arg in args {
+                assert!((-5..=5).contains(&arg.parse::<i64>().unwrap()));
+            }
+        }
+        let generator = build(toml::Generator {
+            kind: Some("words".into()),
+            words: Some(vec!["hello".into(), "world".into()]),
+            max_length: Some(3),
+            count: Some(5),
+            ..Default::default()
+        })?;
+        let cases = generator.cases()?;
+        assert_eq!(cases.len(), 5);
+        for case in cases {
+            assert_eq!(case.args, generator.args(case.seed.unwrap())?);
+            let words: Vec<&str> = case.args[0].split(' ').collect();
+            assert!((1..=3).contains(&words.len()));
+            assert!(words.iter().all(|word| ["hello", "world"].contains(word)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn generator_program() -> Result<(), QuestionError> {
+        let generator = Generator::build_from_toml(
+            toml::Generator {
+                program: Some("gen_ints.py".into()),
+                ..Default::default()
+            },
+            "tst/resources/questions_random/Z_max_of_hardcoded",
+            TIMEOUT,
+            Compare::Exact,
+        )?;
+        let args = generator.args(u64::MAX)?;
+        assert_eq!(args, generator.args(u64::MAX)?);
+        assert!((1..=5).contains(&args.len()));
+        assert!(args.iter().all(|arg| arg.parse::<i64>().is_ok()));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_generators() {
+        for toml in [
+            toml::Generator::default(),
+            toml::Generator {
+                kind: Some("floats".into()),
+                ..Default::default()
+            },
+            toml::Generator {
+                kind: Some("words".into()),
+                ..Default::default()
+            },
+            toml::Generator {
+                kind: Some("strings".into()),
+                min_length: Some(5),
+                max_length: Some(1),
+                ..Default::default()
+            },
+            toml::Generator {
+                kind: Some("ints".into()),
+                program: Some("gen.sh".into()),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                build(toml),
+                Err(QuestionError::InvalidGenerator(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Builds of makefile submissions
+//!
+//! A submission with `submission_type = "makefile"` is built by running `make` in a copy of the
+//! submit directory, so that the object files it leaves behind are not seen by the user or by
+//! the next grading. The Makefile is checked the way a peer evaluation would: it must build the
+//! expected binary or library, must not relink it when nothing has changed, and each of its
+//! required targets must work. Each run of `make` may take as long as the `timeout` in `Config`.
+
+use crate::question::case::Case;
+use crate::question::compiler::{Build, BuildResult};
+use crate::question::test::TestError;
+use crate::question::tree::copy_directory;
+use crate::question::{BinaryResult, Program, QuestionError};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Program used to build makefile submissions
+const MAKE: &str = "make";
+
+/// Targets run when a question does not list its own
+pub const DEFAULT_TARGETS: [&str; 4] = ["all", "clean", "fclean", "re"];
+
+/// Copy `submit_directory` and build `artifact` in the copy, checking that it is not relinked by
+/// a second `make`, and that running each of `targets` works. `fclean` must remove the artifact,
+/// and `all` and `re` must build it. A run of `make` that takes longer than `timeout` is killed.
+pub fn build(
+    submit_directory: &str,
+    artifact: &str,
+    targets: &[String],
+    timeout: Duration,
+) -> Result<BuildResult, QuestionError> {
+    let build = Build::new(artifact)?;
+    let make = Make {
+        dir: build.dir(),
+        timeout,
+    };
+    let checked = copy_directory(submit_directory, build.dir())
+        .and_then(|_| make.check_makefile(artifact, targets));
+    build.finish(checked)
+}
+
+/// Runs of `make` in a copy of the submit directory
+struct Make<'a> {
+    dir: &'a Path,
+    timeout: Duration,
+}
+
+impl Make<'_> {
+    /// Run every check on the Makefile, returning the first that fails
+    fn check_makefile(
+        &self,
+        artifact: &str,
+        targets: &[String],
+    ) -> Result<Option<TestError>, QuestionError> {
+        let path = self.dir.join(artifact);
+        if let Some(error) = self.make(None)? {
+            return Ok(Some(error));
+        }
+        let built = match modified(&path) {
+            Some(built) => built,
+            None => return Ok(Some(not_built(None, artifact))),
+        };
+        if let Some(error) = self.make(None)? {
+            return Ok(Some(error));
+        }
+        if modified(&path) != Some(built) {
+            return Ok(Some(TestError::InvalidMakefile(format!(
+                "`make` relinked {} when nothing had changed",
+                artifact
+            ))));
+        }
+        for target in targets.iter() {
+            if let Some(error) = self.make(Some(target))? {
+                return Ok(Some(error));
+            }
+            match (&target[..], path.exists()) {
+                ("fclean", true) => {
+                    return Ok(Some(TestError::InvalidMakefile(format!(
+                        "`make fclean` did not remove {}",
+                        artifact
+                    ))))
+                }
+                ("all" | "re", false) => return Ok(Some(not_built(Some(target), artifact))),
+                _ => (),
+            }
+        }
+        if !path.exists() {
+            if let Some(error) = self.make(None)? {
+                return Ok(Some(error));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Run `make`, building `target` or the default goal
+    fn make(&self, target: Option<&str>) -> Result<Option<TestError>, QuestionError> {
+        let case = Case {
+            args: target.iter().map(|target| target.to_string()).collect(),
+            ..Case::empty(self.timeout)
+        };
+        let output = match Program::Binary(MAKE).run_in(&case, self.dir)? {
+            BinaryResult::Output(output) => output,
+            BinaryResult::Timeout => {
+                return Ok(Some(TestError::InvalidMakefile(format!(
+                    "`{}` did not finish within {} seconds",
+                    command_name(target),
+                    self.timeout.as_secs_f64()
+                ))))
+            }
+        };
+        if output.code() == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(TestError::InvalidMakefile(format!(
+                "`{}` failed:\n{}",
+                command_name(target),
+                output.stderr()
+            ))))
+        }
+    }
+}
+
+fn not_built(target: Option<&str>, artifact: &str) -> TestError {
+    TestError::InvalidMakefile(format!(
+        "`{}` did not build {}",
+        command_name(target),
+        artifact
+    ))
+}
+
+fn command_name(target: Option<&str>) -> String {
+    match target {
+        Some(target) => format!("{} {}", MAKE, target),
+        None => MAKE.to_string(),
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn build(
+        submit_directory: &str,
+        artifact: &str,
+        targets: &[String],
+    ) -> Result<BuildResult, QuestionError> {
+        super::build(submit_directory, artifact, targets, TIMEOUT)
+    }
+
+    fn targets(targets: &[&str]) -> Vec<String> {
+        targets.iter().map(|target| target.to_string()).collect()
+    }
+
+    #[test]
+    fn makefile_targets() -> Result<(), QuestionError> {
+        let dir = "tst/resources/rendu_test/print_args";
+        // Ending with fclean leaves nothing to test, so the artifact is built again
+        match build(dir, "print_args", &targets(&["all", "fclean"]))? {
+            BuildResult::Ok(build) => {
+                assert!(Path::new(&build.artifact()).exists());
+                build.remove()?;
+            }
+            BuildResult::Err(error) => panic!("Makefile should be valid, not {}", error),
+        }
+        match build(dir, "print_args", &targets(&["all", "bonus"]))? {
+            BuildResult::Err(error) => {
+                assert!(error
+                    .to_string()
+                    .starts_with("Makefile error: `make bonus` failed:"))
+            }
+            BuildResult::Ok(_) => panic!("Makefile has no bonus target"),
+        }
+        match build(dir, "a.out", &[])? {
+            BuildResult::Err(error) => assert_eq!(
+                error.to_string(),
+                "Makefile error: `make` did not build a.out"
+            ),
+            BuildResult::Ok(_) => panic!("Makefile does not build a.out"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn makefile_timeout() -> Result<(), QuestionError> {
+        let dir = "tst/resources/rendu_test/Z_print_args_hangs";
+        let timeout = Duration::from_millis(500);
+        match super::build(dir, "print_args", &[], timeout)? {
+            BuildResult::Err(error) => assert_eq!(
+                error.to_string(),
+                "Makefile error: `make` did not finish within 0.5 seconds"
+            ),
+            BuildResult::Ok(_) => panic!("Makefile never finishes"),
+        }
+        Ok(())
+    }
+}
@@ -1,13 +1,20 @@
 mod binary_runner;
+pub mod case;
+pub mod compare;
 mod compiler;
 pub mod database;
 pub mod error;
+mod function;
+mod generator;
+mod make;
+mod sanitizer;
 mod submission;
 pub mod test;
 mod toml;
 mod trace;
+mod tree;
 
-pub use binary_runner::{run_binary_with_args, BinaryResult};
+pub use binary_runner::{run_binary, BinaryResult, Program};
 pub use database::QuestionDB;
 pub use error::QuestionError;
 pub use trace::Trace;
@@ -37,15 +44,29 @@ pub struct Question {
     difficulty: Option<u32>,
     directories: QuestionDirs,
     submission: Submission,
+    /// Files that may be submitted as well as those the submission requires
+    allowed_files: Vec<String>,
     test: Test,
 }
 
 impl Question {
     pub fn build_from_toml(
         config: &Config,
-        toml: toml::Question,
+        mut toml: toml::Question,
         dir_path: &str,
     ) -> Result<Self, QuestionError> {
+        Question::apply_toolchain(
+            config,
+            &toml.submission.toolchain,
+            &mut toml.submission.compiler,
+            &mut toml.submission.flags,
+        )?;
+        Question::apply_toolchain(
+            config,
+            &toml.test.toolchain,
+            &mut toml.test.compiler,
+            &mut toml.test.flags,
+        )?;
         Question::check_type_validity(&toml)?;
         let name = toml.info.name;
         let submit_directory = format!("{}/{}", config.submit_dir(), name);
@@ -53,11 +74,12 @@ impl Question {
         let subject_directory =
             Self::validate_subject_directory(&question_directory, &toml.test.subject)?;
 
-        let test: Test = Test::build_from_toml(toml.test, dir_path)?;
+        let test: Test = Test::build_from_toml(toml.test, dir_path, config.timeout())?;
         test.invalid_framework(config)
             .map_err(QuestionError::InvalidFramework)?;
 
-        let submission: Submission = Submission::build_from_toml(toml.submission)?;
+        let allowed_files = toml.submission.allowed_files.take().unwrap_or_default();
+        let submission: Submission = Submission::build_from_toml(toml.submission, config)?;
 
         Ok(Self {
             name,
@@ -69,6 +91,7 @@ impl Question {
                 subject_directory,
             },
             submission,
+            allowed_files,
             test,
         })
     }
@@ -94,7 +117,7 @@ impl Question {
                     let buffer = std::fs::read_to_string(file.path())?;
                     let toml: toml::Question = toml_parse::from_str(&buffer)?;
                     let question =
-                        Question::build_from_toml(&config, toml, &dir_path.to_str().unwrap())?;
+                        Question::build_from_toml(config, toml, dir_path.to_str().unwrap())?;
                     question_opt = Some(question);
                 }
             }
@@ -134,6 +157,30 @@ impl Question {
         }
     }
 
+    /// Fill in `compiler` and `flags` from the toolchain named in the question, if any.
+    ///
+    /// A `compiler` given in the question takes precedence over the toolchain's, while the
+    /// question's `flags` are added after the toolchain's default flags.
+    fn apply_toolchain(
+        config: &Config,
+        toolchain: &Option<String>,
+        compiler: &mut Option<String>,
+        flags: &mut Option<Vec<String>>,
+    ) -> Result<(), QuestionError> {
+        if let Some(name) = toolchain {
+            let toolchain = config
+                .get_toolchain(name)
+                .ok_or_else(|| QuestionError::UnknownToolchain(name.clone()))?;
+            if compiler.is_none() {
+                *compiler = Some(toolchain.compiler().to_string());
+            }
+            let mut toolchain_flags = toolchain.flags().to_vec();
+            toolchain_flags.extend(flags.take().unwrap_or_default());
+            *flags = Some(toolchain_flags);
+        }
+        Ok(())
+    }
+
     /// Check that the submission type and test type are valid together - a submission type of
     /// 'executable' is incompatible with a test type of 'unit-test' for example.
     ///
@@ -142,6 +189,73 @@ impl Question {
     ///
     /// If additional types are added in future, then additional validation may be required here.
     fn check_type_validity(toml: &toml::Question) -> Result<(), QuestionError> {
+        if toml.submission.submission_type == "script"
+            && !matches!(
+                &toml.test.test_type[..],
+                "executable" | "sources" | "filesystem"
+            )
+        {
+            return Err(QuestionError::MismatchedQuestion(
+                toml.test.test_type.clone(),
+                toml.submission.submission_type.clone(),
+            ));
+        }
+        if toml.submission.submission_type == "script" && toml.test.leak_check == Some(true) {
+            return Err(QuestionError::InvalidTestType(
+                "leak_check cannot be used with script submissions".into(),
+            ));
+        }
+        // A library is linked against by the tests, and a binary built by a Makefile is run by them
+        let builds_library = match &toml.submission.submission_type[..] {
+            "makefile" => Some(toml.submission.library.is_some()),
+            "library" => Some(true),
+            _ => None,
+        };
+        if builds_library.is_some_and(|library| {
+            library
+                != matches!(
+                    &toml.test.test_type[..],
+                    "expected-output" | "unit-test" | "function"
+                )
+        }) {
+            return Err(QuestionError::MismatchedQuestion(
+                toml.test.test_type.clone(),
+                toml.submission.submission_type.clone(),
+            ));
+        }
+        if toml.test.initial_tree.is_some() && toml.test.test_type != "filesystem" {
+            return Err(QuestionError::InvalidTestType(
+                "initial_tree can only be used with filesystem tests, or in a case".into(),
+            ));
+        }
+        if toml.test.test_type == "filesystem" && toml.test.leak_check == Some(true) {
+            return Err(QuestionError::InvalidTestType(
+                "leak_check cannot be used with filesystem tests".into(),
+            ));
+        }
+        if toml.test.generator.is_some()
+            && !matches!(&toml.test.test_type[..], "executable" | "sources")
+        {
+            return Err(QuestionError::InvalidTestType(
+                "generator can only be used with executable and sources tests".into(),
+            ));
+        }
+        // The functions a Makefile's artifact calls cannot be told apart from those its startup
+        // code and linker add, so they are not checked
+        if toml.submission.submission_type == "makefile"
+            && (toml.submission.allowed_functions.is_some()
+                || toml.submission.forbidden_functions.is_some())
+        {
+            return Err(QuestionError::InvalidSubmissionType(
+                "allowed_functions and forbidden_functions cannot be used with makefile submissions"
+                    .into(),
+            ));
+        }
+        if toml.submission.submission_type != "sources" && toml.test.sanitize == Some(true) {
+            return Err(QuestionError::InvalidTestType(
+                "sanitize can only be used with sources submissions".into(),
+            ));
+        }
         match &toml.test.test_type[..] {
             "executable" => {
                 if toml.submission.submission_type == "sources"
@@ -153,7 +267,36 @@ impl Question {
                 }
             }
             "unit-test" => {
-                if toml.submission.submission_type != "sources" {
+                if !matches!(
+                    &toml.submission.submission_type[..],
+                    "sources" | "makefile" | "library"
+                ) {
+                    Err(QuestionError::MismatchedQuestion(
+                        toml.test.test_type.clone(),
+                        toml.submission.submission_type.clone(),
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            "function" => {
+                if !matches!(
+                    &toml.submission.submission_type[..],
+                    "sources" | "makefile" | "library"
+                ) {
+                    Err(QuestionError::MismatchedQuestion(
+                        toml.test.test_type.clone(),
+                        toml.submission.submission_type.clone(),
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            "filesystem" => {
+                if !matches!(
+                    &toml.submission.submission_type[..],
+                    "executable" | "script"
+                ) {
                     Err(QuestionError::MismatchedQuestion(
                         toml.test.test_type.clone(),
                         toml.submission.submission_type.clone(),
@@ -168,7 +311,50 @@ impl Question {
         }
     }
 
+    /// Every compiler needed to grade this question, for both the submission and the tests
+    pub fn compilers(&self) -> Vec<&str> {
+        let mut compilers = Vec::new();
+        compilers.extend(self.submission.compiler());
+        compilers.extend(self.test.compiler());
+        compilers.dedup();
+        compilers
+    }
+
+    /// Does this question limit which functions the submission may call, or which symbols it may
+    /// export? If so, `nm` is needed to grade it.
+    pub fn restricts_functions(&self) -> bool {
+        self.submission.restricts_functions()
+    }
+
+    /// Is the submission built by its own Makefile? If so, `make` is needed to grade it.
+    pub fn uses_make(&self) -> bool {
+        self.submission.uses_make()
+    }
+
+    /// Is the submission built into a library by Examtrainer? If so, `ar` is needed to grade it.
+    pub fn uses_archiver(&self) -> bool {
+        self.submission.uses_archiver()
+    }
+
+    /// Is the submission rerun under the leak checker in [`Config`] once it passes?
+    pub fn leak_check(&self) -> bool {
+        self.test.leak_check()
+    }
+
+    /// The unit-test framework this question is tested with, if any
+    pub fn framework(&self) -> Option<&str> {
+        self.test.framework()
+    }
+
+    /// Grade the submission, after checking that exactly the files it needs were submitted
     pub fn grade(&self, config: &Config) -> Result<TestResult, QuestionError> {
+        if let Some(error) = submission::check_files(
+            &self.directories.submit_directory,
+            &self.submission.required_files(),
+            &self.allowed_files,
+        )? {
+            return Ok(TestResult::Failed(error));
+        }
         self.test.run(&self.submission, &self.directories, config)
     }
 
@@ -217,10 +403,20 @@ mod tests {
     use crate::question::test::TestError;
     use crate::question::QuestionDB;
     use std::fs;
+
+    // Config directories are canonicalized, so expected paths must be as well
+    fn resource(path: &str) -> String {
+        let path = Path::new("tst/resources")
+            .join(path)
+            .canonicalize()
+            .unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
     #[test]
     fn question_no_subject() -> Result<(), Error> {
         let config = Config::new_from("tst/resources/test_config1.toml")?;
-        let dir_path = format!("{}/{}", config.question_dir(), "no_sub_question");
+        let dir_path = format!("{}/{}", config.question_dirs()[0], "no_sub_question");
         let file = format!("{}/{}", dir_path, "hello_world.toml");
         let buffer = fs::read_to_string(file)?;
         let toml: toml::Question =
@@ -237,7 +433,7 @@ mod tests {
     #[test]
     fn build_valid_question() -> Result<(), Error> {
         let config = Config::new_from("tst/resources/test_config1.toml")?;
-        let dir_path = format!("{}/{}", config.question_dir(), "hello_world");
+        let dir_path = format!("{}/{}", config.question_dirs()[0], "hello_world");
         let file = format!("{}/{}", dir_path, "hello_world.toml");
         let buffer = fs::read_to_string(file)?;
         let toml: toml::Question =
@@ -248,15 +444,15 @@ mod tests {
         assert_eq!(question.name(), "hello_world");
         assert_eq!(
             question.directories().submit_directory,
-            "tst/resources/rendu_test/hello_world"
+            resource("rendu_test/hello_world")
         );
         assert_eq!(
             question.directories().question_directory,
-            "tst/resources/questions/hello_world"
+            resource("questions/hello_world")
         );
         assert_eq!(
             question.directories().subject_directory,
-            "tst/resources/questions/hello_world/hello_world.subject"
+            resource("questions/hello_world/hello_world.subject")
         );
         assert!(matches!(question.test, Test::CompiledTogether(_)));
         assert!(question.has_difficulty_in_range(&Range::new(0, 4)?));
@@ -267,13 +463,11 @@ mod tests {
     #[test]
     fn build_from_dir_entry() -> Result<(), Error> {
         let config = Config::new_from("tst/resources/test_config1.toml")?;
-        let question_dirs = std::fs::read_dir(&config.question_dir())?;
+        let question_dirs = std::fs::read_dir(config.question_dirs()[0])?;
         let mut dir_entry_opt: Option<DirEntry> = None;
-        for dir in question_dirs.into_iter() {
-            if let Ok(dir) = dir {
-                if dir_entry_opt.is_none() && dir.path().to_str().unwrap().contains("hello_world") {
-                    dir_entry_opt = Some(dir);
-                }
+        for dir in question_dirs.into_iter().flatten() {
+            if dir_entry_opt.is_none() && dir.path().ends_with("hello_world") {
+                dir_entry_opt = Some(dir);
             }
         }
         assert!(dir_entry_opt.is_some());
@@ -283,15 +477,15 @@ mod tests {
         let question = question_res?;
         assert_eq!(
             question.directories().submit_directory,
-            "tst/resources/rendu_test/hello_world"
+            resource("rendu_test/hello_world")
         );
         assert_eq!(
             question.directories().question_directory,
-            "tst/resources/questions/hello_world"
+            resource("questions/hello_world")
         );
         assert_eq!(
             question.directories().subject_directory,
-            "tst/resources/questions/hello_world/hello_world.subject"
+            resource("questions/hello_world/hello_world.subject")
         );
         assert!(matches!(question.test, Test::CompiledTogether(_)));
         Ok(())
@@ -300,7 +494,11 @@ mod tests {
     #[test]
     fn question_invalid_sources() -> Result<(), Error> {
         let config = Config::new_from("tst/resources/test_config1.toml")?;
-        let dir_path = format!("{}/{}", config.question_dir(), "Z_no_compiler_countdown");
+        let dir_path = format!(
+            "{}/{}",
+            config.question_dirs()[0],
+            "Z_no_compiler_countdown"
+        );
         let file = format!("{}/{}", dir_path, "ft_countdown.toml");
         let buffer = fs::read_to_string(file)?;
         let toml: toml::Question =
@@ -321,7 +519,7 @@ mod tests {
         let question = question_database.get_question_by_name("Z_failed_countdown");
         assert!(question.is_some());
         let question = question.unwrap();
-        assert_eq!(question.difficulty(), 1);
+        assert_eq!(question.difficulty(), Some(1));
         let test_result = question.grade(&config)?;
         let error = match test_result {
             TestResult::Passed => panic!("Test should have failed"),
@@ -367,7 +565,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
     fn question_timeout() -> Result<(), Error> {
         let config = Config::new_from("tst/resources/test_config1.toml")?;
         let question_database = QuestionDB::new(&config)?;
@@ -386,7 +583,11 @@ mod tests {
     #[test]
     fn invalid_framework() -> Result<(), Error> {
         let config = Config::new_from("tst/resources/test_config2.toml")?;
-        let dir_path = format!("{}/{}", config.question_dir(), "Z_invalid_framework_strlen");
+        let dir_path = format!(
+            "{}/{}",
+            config.question_dirs()[0],
+            "Z_invalid_framework_strlen"
+        );
         let file = format!("{}/{}", dir_path, "ft_strlen.toml");
         let buffer = fs::read_to_string(file)?;
         let toml: toml::Question =
@@ -495,6 +696,351 @@ mod tests {
             },
         }
     }
+
+    #[test]
+    fn question_with_stdin() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("rev_stdin").unwrap();
+        assert!(question.grade(&config)?.is_passed());
+        let question = question_database
+            .get_question_by_name("Z_rev_stdin_wrong")
+            .unwrap();
+        let trace = match question.grade(&config)? {
+            TestResult::Passed => panic!("Test should have failed"),
+            TestResult::Failed(TestError::IncorrectOutput(trace)) => trace.to_string(),
+            TestResult::Failed(e) => panic!("Test should fail with incorrect output, not {}", e),
+        };
+        assert!(trace.contains("With stdin:\nabc\nhello there\n"));
+        assert!(trace.contains("With stdin:\nhello\nworld\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn question_with_cases() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("add").unwrap();
+        assert!(question.grade(&config)?.is_passed());
+        let question = question_database
+            .get_question_by_name("Z_add_wrong")
+            .unwrap();
+        let trace = match question.grade(&config)? {
+            TestResult::Passed => panic!("Test should have failed"),
+            TestResult::Failed(TestError::IncorrectOutput(trace)) => trace.to_string(),
+            TestResult::Failed(e) => panic!("Test should fail with incorrect output, not {}", e),
+        };
+        assert!(trace.contains("Failure in small numbers, with args: 1, 2, \n"));
+        assert!(trace.contains("Expected Stdout:\n3\nActual Stdout:\n-1\n"));
+        assert!(trace.contains("Failure in negative numbers"));
+        assert!(!trace.contains("missing argument"));
+        assert!(trace.ends_with("1 of 3 cases passed\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn question_with_script() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/script_config.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        for name in ["count_args", "count_args_sh"] {
+            let question = question_database.get_question_by_name(name).unwrap();
+            assert_eq!(question.compilers(), vec!["gcc"]);
+            assert!(question.grade(&config)?.is_passed(), "{} should pass", name);
+        }
+        let question = question_database
+            .get_question_by_name("Z_count_args_wrong")
+            .unwrap();
+        assert!(matches!(
+            question.grade(&config)?,
+            TestResult::Failed(TestError::IncorrectOutput(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn question_function() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("ft_atoi").unwrap();
+        assert!(matches!(question.test, Test::Function(_)));
+        assert!(question.grade(&config)?.is_passed());
+        let question = question_database
+            .get_question_by_name("Z_ft_atoi_wrong")
+            .unwrap();
+        let trace = match question.grade(&config)? {
+            TestResult::Passed => panic!("Test should have failed"),
+            TestResult::Failed(TestError::IncorrectOutput(trace)) => trace.to_string(),
+            TestResult::Failed(e) => panic!("Test should fail with incorrect output, not {}", e),
+        };
+        assert!(trace.contains(
+            "Failure in ft_atoi(\"  \\t\\n-1234abc\")\nWrong return value: expected -1234, got 0\n"
+        ));
+        assert!(!trace.contains("ft_atoi(\"42\")"));
+        assert!(trace.ends_with("2 of 5 calls passed\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn question_forbidden_function() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database
+            .get_question_by_name("Z_ft_atoi_forbidden")
+            .unwrap();
+        match question.grade(&config)? {
+            TestResult::Failed(TestError::ForbiddenFunction(name)) => assert_eq!(name, "atoi"),
+            result => panic!(
+                "Test should fail with a forbidden function, not {:?}",
+                result
+            ),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn question_leak_check() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/leak_config.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("join_args").unwrap();
+        assert!(question.leak_check());
+        assert!(question.grade(&config)?.is_passed());
+        let question = question_database
+            .get_question_by_name("Z_join_args_leak")
+            .unwrap();
+        let trace = match question.grade(&config)? {
+            TestResult::Failed(TestError::MemoryLeak(trace)) => trace.to_string(),
+            result => panic!("Test should fail with a memory leak, not {:?}", result),
+        };
+        assert!(trace.starts_with("Memory leak with args: hello, world, \n"));
+        assert!(trace.contains("LeakSanitizer: detected memory leaks"));
+        Ok(())
+    }
+
+    #[test]
+    fn question_sanitize() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("sum_array").unwrap();
+        assert!(question.grade(&config)?.is_passed());
+        // The overflow gives the right output without sanitizers
+        let question = question_database
+            .get_question_by_name("Z_sum_array_overflow")
+            .unwrap();
+        assert!(question.grade(&config)?.is_passed());
+        let config = Config::new_from("tst/resources/sanitize_config.toml")?;
+        assert!(config.sanitize());
+        let trace = match question.grade(&config)? {
+            TestResult::Failed(TestError::UndefinedBehaviour(trace)) => trace.to_string(),
+            result => panic!(
+                "Test should fail with undefined behaviour, not {:?}",
+                result
+            ),
+        };
+        assert!(trace.starts_with("Undefined behaviour with args: 1, 2, 3, \n"));
+        assert!(trace.contains("ERROR: AddressSanitizer: heap-buffer-overflow"));
+        assert!(trace.contains(" in sum_array "));
+        Ok(())
+    }
+
+    #[test]
+    fn question_with_unexpected_files() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database
+            .get_question_by_name("Z_add_files")
+            .unwrap();
+        match question.grade(&config)? {
+            TestResult::Failed(error @ TestError::InvalidFiles { .. }) => assert_eq!(
+                error.to_string(),
+                "Submitted files are not as expected: unexpected file: a.out, \
+                 unexpected file: notes/todo.txt"
+            ),
+            result => panic!("Test should fail with invalid files, not {:?}", result),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn question_makefile() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/make_config.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        for name in ["print_args", "ft_strlen_lib"] {
+            let question = question_database.get_question_by_name(name).unwrap();
+            assert!(question.uses_make());
+            assert!(question.grade(&config)?.is_passed(), "{} should pass", name);
+        }
+        let question = question_database
+            .get_question_by_name("Z_print_args_relink")
+            .unwrap();
+        match question.grade(&config)? {
+            TestResult::Failed(error @ TestError::InvalidMakefile(_)) => assert_eq!(
+                error.to_string(),
+                "Makefile error: `make` relinked print_args when nothing had changed"
+            ),
+            result => panic!("Test should fail with a relink, not {:?}", result),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn makefile_function_rules() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/make_config.toml")?;
+        let buffer = fs::read_to_string(resource("questions_make/print_args/print_args.toml"))?;
+        let mut toml: toml::Question =
+            toml_parse::from_str(&buffer).map_err(|e| Error::Question(e.into()))?;
+        toml.submission.allowed_functions = Some(vec!["write".into()]);
+        let question =
+            Question::build_from_toml(&config, toml, &resource("questions_make/print_args"));
+        assert!(matches!(
+            question,
+            Err(QuestionError::InvalidSubmissionType(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn question_library() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/library_config.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("libft_str").unwrap();
+        assert!(question.uses_archiver());
+        assert!(question.grade(&config)?.is_passed());
+        let question = question_database
+            .get_question_by_name("Z_libft_str_exports")
+            .unwrap();
+        match question.grade(&config)? {
+            TestResult::Failed(error @ TestError::InvalidExports { .. }) => assert_eq!(
+                error.to_string(),
+                "Library symbols are not as expected: unexpected symbol: copy_char"
+            ),
+            result => panic!("Test should fail with an exported helper, not {:?}", result),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn question_generator() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/random_config.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("max_of").unwrap();
+        assert!(question.grade(&config)?.is_passed());
+        // Passes the fixed args, by hardcoding their output
+        let question = question_database
+            .get_question_by_name("Z_max_of_hardcoded")
+            .unwrap();
+        let trace = match question.grade(&config)? {
+            TestResult::Failed(TestError::IncorrectOutput(trace)) => trace.to_string(),
+            result => panic!("Test should fail with random args, not {:?}", result),
+        };
+        assert!(trace.contains("Generated from seed: "));
+        Ok(())
+    }
+
+    #[test]
+    fn question_filesystem() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/filesystem_config.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("ex00").unwrap();
+        assert!(question.grade(&config)?.is_passed());
+        let question = question_database
+            .get_question_by_name("Z_ex00_wrong")
+            .unwrap();
+        let trace = match question.grade(&config)? {
+            TestResult::Failed(TestError::IncorrectOutput(trace)) => trace.to_string(),
+            result => panic!(
+                "Test should fail on the files left behind, not {:?}",
+                result
+            ),
+        };
+        assert!(trace.starts_with("Wrong files with args: \n"));
+        assert!(trace.contains("dir should have permissions 0715, not "));
+        assert!(trace.contains("link should link to z, not Z\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn question_output_files() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/filesystem_config.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("ft_cp").unwrap();
+        assert!(question.grade(&config)?.is_passed());
+        let question = question_database
+            .get_question_by_name("Z_ft_cp_wrong")
+            .unwrap();
+        let trace = match question.grade(&config)? {
+            TestResult::Failed(TestError::IncorrectOutput(trace)) => trace.to_string(),
+            result => panic!("Test should fail on the file written, not {:?}", result),
+        };
+        assert!(trace.starts_with("Failure in copy, with args: in.txt, out.txt, \n"));
+        assert!(
+            trace.contains("Wrong contents in out.txt\nExpected:\nhello\nworld\n\nActual:\nhell\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn question_with_tolerance() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("average").unwrap();
+        assert!(question.grade(&config)?.is_passed());
+        let question = question_database
+            .get_question_by_name("Z_average_wrong")
+            .unwrap();
+        assert!(matches!(
+            question.grade(&config)?,
+            TestResult::Failed(TestError::IncorrectOutput(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn question_crashes() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database
+            .get_question_by_name("Z_add_crash")
+            .unwrap();
+        match question.grade(&config)? {
+            TestResult::Failed(error @ TestError::Crashed { .. }) => {
+                assert!(matches!(
+                    &error,
+                    TestError::Crashed { signal: 11, args } if args == &["1", "2"]
+                ));
+                assert!(error.to_string().ends_with("Segmentation fault (SIGSEGV)"));
+            }
+            result => panic!("Test should fail with a crash, not {:?}", result),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn question_with_toolchain() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/toolchain_config.toml")?;
+        let question_database = QuestionDB::new(&config)?;
+        let question = question_database.get_question_by_name("hello_world");
+        assert!(question.is_some());
+        let question = question.unwrap();
+        assert_eq!(question.compilers(), vec!["gcc"]);
+        assert!(question.grade(&config)?.is_passed());
+        Ok(())
+    }
+
+    #[test]
+    fn question_unknown_toolchain() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
+        let buffer =
+            fs::read_to_string(resource("questions_toolchain/hello_world/hello_world.toml"))?;
+        let toml: toml::Question =
+            toml_parse::from_str(&buffer).map_err(|e| Error::Question(e.into()))?;
+        let question =
+            Question::build_from_toml(&config, toml, &resource("questions_toolchain/hello_world"));
+        assert!(matches!(
+            question,
+            Err(QuestionError::UnknownToolchain(name)) if name == "c"
+        ));
+        Ok(())
+    }
 }
 
 #[cfg(test)]
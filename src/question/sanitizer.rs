@@ -0,0 +1,81 @@
+//! Sanitizer-instrumented builds of a submission
+//!
+//! Reading one past the end of a buffer often gives the right output by luck. A question with
+//! `sanitize = true` in its `[test]` table, or every question when `sanitize = true` is in the
+//! `[grading]` table of the config, rebuilds a passing submission with AddressSanitizer and
+//! UndefinedBehaviorSanitizer, and runs every case against that build too. Any report fails the
+//! submission, whatever its output.
+
+/// Flags added to the usual ones when building the instrumented binary
+pub const FLAGS: [&str; 3] = [
+    "-fsanitize=address,undefined",
+    "-fno-omit-frame-pointer",
+    "-g",
+];
+
+/// Number of stack frames of a report kept in the trace
+const MAX_FRAMES: usize = 5;
+
+/// The start of the first AddressSanitizer or UndefinedBehaviorSanitizer report in `stderr`, if
+/// any: the error itself, and up to [`MAX_FRAMES`] frames of its stack trace. Leaks found by
+/// AddressSanitizer are not undefined behaviour, and are left to `leak_check`.
+pub fn report(stderr: &str) -> Option<String> {
+    let mut lines = stderr.lines().skip_while(|line| {
+        !(line.contains("ERROR: AddressSanitizer") || line.contains(": runtime error: "))
+    });
+    let error = lines.next()?;
+    let mut report = without_pid(error).to_string();
+    let mut frames = 0;
+    for line in lines.take_while(|line| !line.trim().is_empty()) {
+        if line.trim_start().starts_with('#') {
+            if frames == MAX_FRAMES {
+                break;
+            }
+            frames += 1;
+        }
+        report += "\n";
+        report += line;
+    }
+    Some(report)
+}
+
+/// Strip the `==1234==` that AddressSanitizer starts its lines with
+fn without_pid(line: &str) -> &str {
+    line.strip_prefix("==")
+        .and_then(|rest| rest.split_once("=="))
+        .map_or(line, |(_, rest)| rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizer_reports() {
+        let asan = "output\n\
+            =================================================================\n\
+            ==14729==ERROR: AddressSanitizer: heap-buffer-overflow on address 0x602000000014\n\
+            READ of size 1 at 0x602000000014 thread T0\n    \
+            #0 0x5641d486a3a4 in main /tmp/ub.c:8\n    \
+            #1 0x7f7754e45249  (/lib/x86_64-linux-gnu/libc.so.6+0x27249)\n\
+            \n\
+            SUMMARY: AddressSanitizer: heap-buffer-overflow /tmp/ub.c:8 in main\n";
+        assert_eq!(
+            report(asan).unwrap(),
+            "ERROR: AddressSanitizer: heap-buffer-overflow on address 0x602000000014\n\
+             READ of size 1 at 0x602000000014 thread T0\n    \
+             #0 0x5641d486a3a4 in main /tmp/ub.c:8\n    \
+             #1 0x7f7754e45249  (/lib/x86_64-linux-gnu/libc.so.6+0x27249)"
+        );
+        let frames: String = (0..10).map(|i| format!("    #{} in f\n", i)).collect();
+        let ubsan = format!(
+            "ub.c:9:61: runtime error: signed integer overflow\n{}",
+            frames
+        );
+        let ubsan = report(&ubsan).unwrap();
+        assert!(ubsan.starts_with("ub.c:9:61: runtime error: signed integer overflow\n"));
+        assert!(ubsan.ends_with("#4 in f"));
+        assert!(report("==1==ERROR: LeakSanitizer: detected memory leaks\n").is_none());
+        assert!(report("").is_none());
+    }
+}
@@ -1,8 +1,17 @@
+use crate::config::{Config, Interpreter};
 use crate::question;
+use crate::question::compiler::{self, remove_binary, Build, BuildResult, CompileResult, Compiler};
 use crate::question::error::MissingKeys;
-use crate::question::QuestionError;
+use crate::question::make;
+use crate::question::test::TestError;
+use crate::question::{Program, QuestionDirs, QuestionError};
+use std::path::Path;
+use std::time::Duration;
 // use crate::utils::ProgramOutput; // TODO needed later
 
+/// The file a [`Makefile`] submission must contain
+const MAKEFILE: &str = "Makefile";
+
 #[derive(Debug)]
 pub struct Exec {
     binary: String,
@@ -19,6 +28,11 @@ impl Exec {
     pub fn name(&self) -> &str {
         &self.binary
     }
+
+    /// Path of the binary, relative to the current directory
+    pub fn path(&self, dirs: &QuestionDirs) -> String {
+        format!("{}/{}", dirs.submit_directory, self.binary)
+    }
 }
 
 #[derive(Debug)]
@@ -26,6 +40,9 @@ pub struct Sources {
     sources: Vec<String>,
     compiler: Option<String>,
     flags: Option<Vec<String>>,
+    /// If given, the only library functions the submission may call
+    allowed_functions: Option<Vec<String>>,
+    forbidden_functions: Vec<String>,
 }
 
 impl Sources {
@@ -35,6 +52,8 @@ impl Sources {
                 sources,
                 compiler: toml.compiler,
                 flags: toml.flags,
+                allowed_functions: toml.allowed_functions,
+                forbidden_functions: toml.forbidden_functions.unwrap_or_default(),
             }),
             _ => Err(MissingKeys::SubSources),
         }
@@ -53,35 +72,415 @@ impl Sources {
     pub fn flags(&self) -> &Option<Vec<String>> {
         &self.flags
     }
+
+    /// Paths of the sources, relative to the current directory
+    pub fn paths(&self, dirs: &QuestionDirs) -> Vec<String> {
+        self.sources
+            .iter()
+            .map(|source| format!("{}/{}", dirs.submit_directory, source))
+            .collect()
+    }
+
+    /// Does the question limit which functions the submission may call?
+    pub fn restricts_functions(&self) -> bool {
+        self.allowed_functions.is_some() || !self.forbidden_functions.is_empty()
+    }
+
+    pub fn is_forbidden(&self, function: &str) -> bool {
+        self.forbidden_functions.iter().any(|name| name == function)
+            || self
+                .allowed_functions
+                .as_ref()
+                .is_some_and(|allowed| !allowed.iter().any(|name| name == function))
+    }
+
+    /// Compile the submission with `compiler`, which has been given its sources. If the
+    /// submission calls a function it is not allowed to, the binary is removed and compilation
+    /// fails with [`TestError::ForbiddenFunction`].
+    pub fn compile(
+        &self,
+        compiler: &Compiler,
+        dirs: &QuestionDirs,
+    ) -> Result<CompileResult, QuestionError> {
+        let binary = match compiler.compile()? {
+            CompileResult::Ok(binary) => binary,
+            error => return Ok(error),
+        };
+        let forbidden = self.forbidden_function(compiler, dirs);
+        if !matches!(forbidden, Ok(None)) {
+            remove_binary(&format!("./{}", binary))?;
+        }
+        match forbidden? {
+            Some(name) => Ok(CompileResult::Err(TestError::ForbiddenFunction(name))),
+            None => Ok(CompileResult::Ok(binary)),
+        }
+    }
+
+    /// The first function the submission calls that it is not allowed to, if any
+    fn forbidden_function(
+        &self,
+        compiler: &Compiler,
+        dirs: &QuestionDirs,
+    ) -> Result<Option<String>, QuestionError> {
+        if !self.restricts_functions() {
+            return Ok(None);
+        }
+        let used = compiler.undefined_functions(&self.paths(dirs))?;
+        Ok(used.into_iter().find(|name| self.is_forbidden(name)))
+    }
+}
+
+/// Sources built into a static library by Examtrainer, which the tests are linked against
+#[derive(Debug)]
+pub struct Library {
+    sources: Sources,
+    library: String,
+    /// If given, the only symbols the library may define, other than static ones
+    exports: Option<Vec<String>>,
+}
+
+impl Library {
+    fn build_from_toml(mut toml: question::toml::Submission) -> Result<Self, MissingKeys> {
+        let (library, exports) = (toml.library.take(), toml.exports.take());
+        match (library, &toml.compiler, &toml.sources) {
+            (Some(library), Some(_), Some(_)) => Ok(Self {
+                sources: Sources::build_from_toml(toml)?,
+                library,
+                exports,
+            }),
+            _ => Err(MissingKeys::SubLibrary),
+        }
+    }
+
+    /// Build the library in a temporary directory, checking the functions it calls and the
+    /// symbols it exports
+    pub fn build(&self, dirs: &QuestionDirs) -> Result<BuildResult, QuestionError> {
+        let mut compiler = Compiler::new(self.sources.compiler());
+        for source in self.sources.paths(dirs) {
+            compiler.add_source(source);
+        }
+        if let Some(flags) = self.sources.flags() {
+            for flag in flags.iter() {
+                compiler.add_flag(flag);
+            }
+        }
+        let build = Build::new(&self.library)?;
+        let checked = self.check_build(&compiler, &build, dirs);
+        build.finish(checked)
+    }
+
+    fn check_build(
+        &self,
+        compiler: &Compiler,
+        build: &Build,
+        dirs: &QuestionDirs,
+    ) -> Result<Option<TestError>, QuestionError> {
+        if let CompileResult::Err(error) = compiler.compile_library(Path::new(&build.artifact()))? {
+            return Ok(Some(error));
+        }
+        if let Some(name) = self.sources.forbidden_function(compiler, dirs)? {
+            return Ok(Some(TestError::ForbiddenFunction(name)));
+        }
+        match &self.exports {
+            Some(exports) => check_exports(&build.artifact(), exports),
+            None => Ok(None),
+        }
+    }
+}
+
+/// A script in the submit directory, run by one of the interpreters in [`Config`]
+#[derive(Debug)]
+pub struct Script {
+    script: String,
+    interpreter: Interpreter,
+}
+
+impl Script {
+    fn build_from_toml(
+        toml: question::toml::Submission,
+        config: &Config,
+    ) -> Result<Self, QuestionError> {
+        match (toml.script, toml.interpreter) {
+            (Some(script), Some(name)) => {
+                let interpreter = config
+                    .get_interpreter(&name)
+                    .ok_or(QuestionError::UnknownInterpreter(name))?;
+                Ok(Self {
+                    script,
+                    interpreter: interpreter.clone(),
+                })
+            }
+            _ => Err(MissingKeys::SubScript.into()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.script
+    }
+
+    /// Path of the script, relative to the current directory
+    pub fn path(&self, dirs: &QuestionDirs) -> String {
+        format!("{}/{}", dirs.submit_directory, self.script)
+    }
+
+    /// The [`Program`] running the script at `path`
+    pub fn program<'a>(&'a self, path: &'a str) -> Program<'a> {
+        Program::Script {
+            interpreter: &self.interpreter,
+            script: path,
+        }
+    }
+}
+
+/// What the Makefile of a [`Makefile`] submission must build
+#[derive(Debug, PartialEq)]
+pub enum Artifact {
+    /// A binary, which is run by the tests
+    Binary(String),
+    /// A static library, which the tests are linked against
+    Library(String),
+}
+
+impl Artifact {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Binary(name) | Self::Library(name) => name,
+        }
+    }
+}
+
+/// Sources built by the submitted Makefile, rather than by a [`Compiler`]
+#[derive(Debug)]
+pub struct Makefile {
+    artifact: Artifact,
+    /// Files submitted along with the Makefile
+    sources: Vec<String>,
+    /// Targets that must work, run in order after the artifact has been built
+    targets: Vec<String>,
+    /// If given, the only symbols a library may define, other than static ones
+    exports: Option<Vec<String>>,
+    /// How long each run of `make` may take
+    timeout: Duration,
+}
+
+impl Makefile {
+    fn build_from_toml(
+        toml: question::toml::Submission,
+        config: &Config,
+    ) -> Result<Self, MissingKeys> {
+        let artifact = match (toml.binary, toml.library) {
+            (Some(binary), None) => Artifact::Binary(binary),
+            (None, Some(library)) => Artifact::Library(library),
+            _ => return Err(MissingKeys::SubMakefile),
+        };
+        Ok(Self {
+            artifact,
+            sources: toml.sources.unwrap_or_default(),
+            targets: toml.targets.unwrap_or_else(|| {
+                make::DEFAULT_TARGETS
+                    .iter()
+                    .map(|target| target.to_string())
+                    .collect()
+            }),
+            exports: toml.exports,
+            timeout: config.timeout(),
+        })
+    }
+
+    pub fn artifact(&self) -> &Artifact {
+        &self.artifact
+    }
+
+    /// Build the artifact in a copy of the submit directory, checking the Makefile on the way,
+    /// and the symbols a library exports
+    pub fn build(&self, dirs: &QuestionDirs) -> Result<BuildResult, QuestionError> {
+        let build = make::build(
+            &dirs.submit_directory,
+            self.artifact.name(),
+            &self.targets,
+            self.timeout,
+        )?;
+        match (build, &self.artifact, &self.exports) {
+            (BuildResult::Ok(build), Artifact::Library(_), Some(exports)) => {
+                let checked = check_exports(&build.artifact(), exports);
+                build.finish(checked)
+            }
+            (build, _, _) => Ok(build),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum Submission {
     Exec(Exec),
     Sources(Sources),
+    Script(Script),
+    Makefile(Makefile),
+    Library(Library),
 }
 
 impl Submission {
-    pub fn build_from_toml(toml: question::toml::Submission) -> Result<Self, QuestionError> {
+    /// Build a [`Submission`] from the `[submission]` table of a Question .toml file, looking up
+    /// the interpreter of a script in `config`
+    pub fn build_from_toml(
+        toml: question::toml::Submission,
+        config: &Config,
+    ) -> Result<Self, QuestionError> {
         match &toml.submission_type[..] {
             "executable" => Ok(Self::Exec(Exec::build_from_toml(toml)?)),
             "sources" => Ok(Self::Sources(Sources::build_from_toml(toml)?)),
+            "script" => Ok(Self::Script(Script::build_from_toml(toml, config)?)),
+            "makefile" => Ok(Self::Makefile(Makefile::build_from_toml(toml, config)?)),
+            "library" => Ok(Self::Library(Library::build_from_toml(toml)?)),
             invalid => Err(QuestionError::InvalidSubmissionType(invalid.into())),
         }
     }
+
+    /// The compiler used to build the submission, if it is compiled separately from the tests
+    pub fn compiler(&self) -> Option<&str> {
+        match self {
+            Self::Sources(sources) => sources.compiler.as_deref(),
+            Self::Library(library) => library.sources.compiler.as_deref(),
+            Self::Exec(_) | Self::Script(_) | Self::Makefile(_) => None,
+        }
+    }
+
+    /// The files a user must submit, relative to the submit directory
+    pub fn required_files(&self) -> Vec<&str> {
+        match self {
+            Self::Exec(exec) => vec![exec.name()],
+            Self::Sources(sources) | Self::Library(Library { sources, .. }) => {
+                sources.sources.iter().map(|s| &s[..]).collect()
+            }
+            Self::Script(script) => vec![script.name()],
+            Self::Makefile(makefile) => std::iter::once(MAKEFILE)
+                .chain(makefile.sources.iter().map(|s| &s[..]))
+                .collect(),
+        }
+    }
+
+    /// Does the question limit which functions the submission may call, or which symbols it
+    /// may export?
+    pub fn restricts_functions(&self) -> bool {
+        match self {
+            Self::Sources(sources) => sources.restricts_functions(),
+            Self::Library(library) => {
+                library.sources.restricts_functions() || library.exports.is_some()
+            }
+            Self::Makefile(makefile) => makefile.exports.is_some(),
+            Self::Exec(_) | Self::Script(_) => false,
+        }
+    }
+
+    /// Is the submission built by its own Makefile? If so, `make` is needed to grade it.
+    pub fn uses_make(&self) -> bool {
+        matches!(self, Self::Makefile(_))
+    }
+
+    /// Is the submission built into a library by Examtrainer? If so, `ar` is needed to grade it.
+    pub fn uses_archiver(&self) -> bool {
+        matches!(self, Self::Library(_))
+    }
+}
+
+/// Check the symbols the static library at `library` defines against the `exports` a question
+/// declared, returning [`TestError::InvalidExports`] if one is missing or any other is exported.
+pub fn check_exports(
+    library: &str,
+    exports: &[String],
+) -> Result<Option<TestError>, QuestionError> {
+    let exported = compiler::exported_symbols(Path::new(library))?;
+    let missing: Vec<String> = exports
+        .iter()
+        .filter(|name| !exported.contains(name))
+        .cloned()
+        .collect();
+    let unexpected: Vec<String> = exported
+        .into_iter()
+        .filter(|name| !exports.contains(name))
+        .collect();
+    if missing.is_empty() && unexpected.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(TestError::InvalidExports {
+            missing,
+            unexpected,
+        }))
+    }
+}
+
+/// Check the files in the submit directory `dir` against those `required` by a question, and the
+/// others it `allowed`, the way the exam shell does before grading. Returns
+/// [`TestError::InvalidFiles`] if a required file is missing, or any other file was submitted.
+pub fn check_files(
+    dir: &str,
+    required: &[&str],
+    allowed: &[String],
+) -> Result<Option<TestError>, QuestionError> {
+    let mut submitted = Vec::new();
+    list_files(Path::new(dir), "", &mut submitted)?;
+    let missing: Vec<String> = required
+        .iter()
+        .filter(|file| !submitted.iter().any(|submitted| submitted == *file))
+        .map(|file| file.to_string())
+        .collect();
+    let unexpected: Vec<String> = submitted
+        .into_iter()
+        .filter(|file| !required.contains(&&file[..]) && !allowed.contains(file))
+        .collect();
+    if missing.is_empty() && unexpected.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(TestError::InvalidFiles {
+            missing,
+            unexpected,
+        }))
+    }
+}
+
+/// Add the path of every file under `dir` to `files`, relative to the submit directory, in
+/// which `dir` is at `prefix`. A directory that does not exist holds no files. Hidden files and
+/// directories are skipped, as the submit directory is usually a git repository, and may hold
+/// files such as `.DS_Store` or editor swap files.
+fn list_files(dir: &Path, prefix: &str, files: &mut Vec<String>) -> Result<(), QuestionError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut entries = entries.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = format!("{}{}", prefix, name);
+        if entry.file_type()?.is_dir() {
+            list_files(&entry.path(), &format!("{}/", path), files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
+    use crate::question::compiler::temp_dir;
     use crate::question::toml;
+    use crate::question::tree::copy_directory;
     use std::fs;
     #[test]
-    fn read_submission_toml() -> Result<(), QuestionError> {
+    fn read_submission_toml() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/test_config1.toml")?;
         let buffer = fs::read_to_string("tst/resources/question_1.toml")?;
-        let question_toml: toml::Question = toml_parse::from_str(&buffer)?;
+        let question_toml: toml::Question =
+            toml_parse::from_str(&buffer).map_err(QuestionError::from)?;
         let submission_toml: toml::Submission = question_toml.submission;
-        let submission: Submission = Submission::build_from_toml(submission_toml)?;
+        let submission: Submission = Submission::build_from_toml(submission_toml, &config)?;
         assert!(matches!(submission, Submission::Sources(_)));
         match submission {
             Submission::Sources(sources) => {
@@ -90,7 +489,110 @@ mod tests {
                 assert_eq!(sources.flags, None);
                 Ok(())
             }
-            _ => Err(QuestionError::InvalidSubmissionType("not good".into())),
+            _ => Err(QuestionError::InvalidSubmissionType("not good".into()).into()),
+        }
+    }
+
+    #[test]
+    fn submitted_files() -> Result<(), Error> {
+        let dir = "tst/resources/rendu_test/Z_add_files";
+        let allowed = ["notes/todo.txt".to_string()];
+        assert!(check_files(dir, &["add.c", "a.out"], &allowed)?.is_none());
+        match check_files(dir, &["add.c", "helpers.c"], &[])? {
+            Some(TestError::InvalidFiles {
+                missing,
+                unexpected,
+            }) => {
+                assert_eq!(missing, ["helpers.c"]);
+                assert_eq!(unexpected, ["a.out", "notes/todo.txt"]);
+            }
+            result => panic!("Files should be invalid, not {:?}", result),
+        }
+        match check_files("tst/resources/rendu_test/missing", &["add.c"], &[])? {
+            Some(TestError::InvalidFiles { missing, .. }) => assert_eq!(missing, ["add.c"]),
+            result => panic!("Files should be missing, not {:?}", result),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn hidden_files_are_ignored() -> Result<(), Error> {
+        // A .git directory cannot be committed, so it is added to a copy of the submit directory
+        let dir = temp_dir("rendu")?;
+        copy_directory("tst/resources/rendu_test/Z_add_hidden_files", &dir)?;
+        fs::create_dir_all(dir.join(".git/objects/ab"))?;
+        fs::write(dir.join(".git/HEAD"), "ref: refs/heads/master\n")?;
+        fs::write(dir.join(".git/objects/ab/cdef"), "")?;
+        let result = check_files(&dir.display().to_string(), &["add.c"], &[]);
+        fs::remove_dir_all(&dir)?;
+        assert!(result?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn function_rules() -> Result<(), Error> {
+        let toml =
+            |allowed: Option<Vec<String>>, forbidden: Option<Vec<String>>| toml::Submission {
+                submission_type: "sources".into(),
+                sources: Some(vec!["ft_putstr.c".into()]),
+                binary: None,
+                library: None,
+                targets: None,
+                exports: None,
+                compiler: Some("gcc".into()),
+                toolchain: None,
+                flags: None,
+                allowed_functions: allowed,
+                forbidden_functions: forbidden,
+                allowed_files: None,
+                script: None,
+                interpreter: None,
+            };
+        let sources = Sources::build_from_toml(toml(None, None)).map_err(QuestionError::from)?;
+        assert!(!sources.restricts_functions());
+        assert!(!sources.is_forbidden("printf"));
+        let sources = Sources::build_from_toml(toml(Some(vec!["write".into()]), None))
+            .map_err(QuestionError::from)?;
+        assert!(sources.restricts_functions());
+        assert!(!sources.is_forbidden("write"));
+        assert!(sources.is_forbidden("printf"));
+        let sources = Sources::build_from_toml(toml(None, Some(vec!["printf".into()])))
+            .map_err(QuestionError::from)?;
+        assert!(!sources.is_forbidden("write"));
+        assert!(sources.is_forbidden("printf"));
+        Ok(())
+    }
+
+    #[test]
+    fn script_submission() -> Result<(), Error> {
+        let config = Config::new_from("tst/resources/script_config.toml")?;
+        let toml = |interpreter: &str| toml::Submission {
+            submission_type: "script".into(),
+            sources: None,
+            binary: None,
+            library: None,
+            targets: None,
+            exports: None,
+            compiler: None,
+            toolchain: None,
+            flags: None,
+            allowed_functions: None,
+            forbidden_functions: None,
+            allowed_files: None,
+            script: Some("count_args.py".into()),
+            interpreter: Some(interpreter.into()),
+        };
+        match Submission::build_from_toml(toml("python"), &config)? {
+            Submission::Script(script) => {
+                assert_eq!(script.name(), "count_args.py");
+                assert_eq!(script.interpreter.program(), "python3");
+            }
+            _ => panic!("Submission should be a script"),
         }
+        assert!(matches!(
+            Submission::build_from_toml(toml("ruby"), &config),
+            Err(QuestionError::UnknownInterpreter(_))
+        ));
+        Ok(())
     }
 }
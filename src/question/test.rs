@@ -5,27 +5,43 @@
 //! using the [`Test::build_from_toml`] constructor. Different types of tests require different
 //! parameters, the different test types are:
 //! * 'executable' - Expects a user to submit their own sources/executable as an answer, then will
-//! run both the user's executable and the test executable side by side: comparing output (both
-//! stdout and stderr), and generating a trace file if necessary.
+//!   run both the user's executable and the test executable side by side: comparing output (both
+//!   stdout and stderr), and generating a trace file if necessary.
 //! * 'unit-test' - Takes source files from a user, and compiles them with Unit Test files supplied
-//! in the Question module. Runs the Unit Test, and places output directly in a trace code if the
-//! Unit Test returns a non-zero value.
+//!   in the Question module. Runs the Unit Test, and places output directly in a trace code if the
+//!   Unit Test returns a non-zero value.
 //! * 'sources' - Functions identically to the 'expected' test type, except requires that the
-//! Question module contains sources to be compiled into an executable before testing.
+//!   Question module contains sources to be compiled into an executable before testing.
 //! * 'expected-output' - Compiles user code together with test sources to produce an executable.
-//! The executable will then be run, with stdout compared against a
-//! `.out` file, and stderr compared against a `.err` file.
+//!   The executable will then be run, with stdout compared against a
+//!   `.out` file, and stderr compared against a `.err` file.
+//! * 'function' - Generates a harness from the prototype of a function and a list of calls to it,
+//!   compiles it with the user's sources, and checks the return value of each call.
+//! * 'filesystem' - Runs the user's script or executable and a test executable, each in a fresh
+//!   directory seeded from the Question module, then compares the files they leave behind as well
+//!   as their output.
 
 use crate::config::Config;
 use crate::question;
-use crate::question::compiler::{remove_binary, CompileResult, Compiler};
+use crate::question::case::{self, Case};
+use crate::question::compare::Compare;
+use crate::question::compiler::{remove_binary, BuildResult, CompileResult, Compiler};
 use crate::question::error::MissingKeys;
+use crate::question::function::{self, Call, Prototype, ReturnType};
+use crate::question::generator::Generator;
+use crate::question::sanitizer;
+use crate::question::tree::{Tree, Workspace};
 use crate::question::{
-    run_binary_with_args, BinaryResult, QuestionDirs, QuestionError, Submission, Trace,
+    run_binary, BinaryResult, Program, QuestionDirs, QuestionError, Submission, Trace,
 };
+use crate::utils::program_output::signal_description;
+use crate::utils::ProgramOutput;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
 use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum TestError {
@@ -33,6 +49,32 @@ pub enum TestError {
     IncorrectOutput(Trace),
     FailedUnitTest(Trace),
     Timeout,
+    Crashed {
+        signal: i32,
+        args: Vec<String>,
+    },
+    ForbiddenFunction(String),
+    MemoryLeak(Trace),
+    UndefinedBehaviour(Trace),
+    InvalidFiles {
+        missing: Vec<String>,
+        unexpected: Vec<String>,
+    },
+    InvalidMakefile(String),
+    InvalidExports {
+        missing: Vec<String>,
+        unexpected: Vec<String>,
+    },
+}
+
+impl TestError {
+    /// A [`TestError::Crashed`] if the run of `case` that produced `output` was killed by a signal
+    fn crash(case: &Case, output: &ProgramOutput) -> Option<Self> {
+        output.signal().map(|signal| Self::Crashed {
+            signal,
+            args: case.args.clone(),
+        })
+    }
 }
 
 impl fmt::Display for TestError {
@@ -42,6 +84,56 @@ impl fmt::Display for TestError {
             Self::IncorrectOutput(trace) => write!(f, "Incorrect Output, Trace: {}", trace),
             Self::FailedUnitTest(trace) => write!(f, "Unit test failed, Trace: {}", trace),
             Self::Timeout => write!(f, "Submission executable timed out"),
+            Self::Crashed { signal, args } => write!(
+                f,
+                "Submission executable crashed with args [{}]: {}",
+                args.join(", "),
+                signal_description(*signal)
+            ),
+            Self::ForbiddenFunction(name) => write!(f, "Forbidden function used: {}", name),
+            Self::MemoryLeak(trace) => write!(f, "Memory leak, Trace: {}", trace),
+            Self::UndefinedBehaviour(trace) => {
+                write!(f, "Undefined behaviour, Trace: {}", trace)
+            }
+            Self::InvalidFiles {
+                missing,
+                unexpected,
+            } => {
+                let problems: Vec<String> = missing
+                    .iter()
+                    .map(|file| format!("missing: {}", file))
+                    .chain(
+                        unexpected
+                            .iter()
+                            .map(|file| format!("unexpected file: {}", file)),
+                    )
+                    .collect();
+                write!(
+                    f,
+                    "Submitted files are not as expected: {}",
+                    problems.join(", ")
+                )
+            }
+            Self::InvalidMakefile(s) => write!(f, "Makefile error: {}", s),
+            Self::InvalidExports {
+                missing,
+                unexpected,
+            } => {
+                let problems: Vec<String> = missing
+                    .iter()
+                    .map(|name| format!("missing: {}", name))
+                    .chain(
+                        unexpected
+                            .iter()
+                            .map(|name| format!("unexpected symbol: {}", name)),
+                    )
+                    .collect();
+                write!(
+                    f,
+                    "Library symbols are not as expected: {}",
+                    problems.join(", ")
+                )
+            }
         }
     }
 }
@@ -51,17 +143,36 @@ impl std::error::Error for TestError {}
 #[derive(Debug)]
 pub struct Exec {
     binary: String,
-    args: Vec<Vec<String>>,
+    cases: Vec<Case>,
+    generator: Option<Generator>,
+    checks: ExtraChecks,
 }
 
 impl Exec {
-    fn build_from_toml(toml: question::toml::Test, dir_path: &str) -> Result<Self, MissingKeys> {
-        match (toml.binary, toml.args) {
-            (Some(binary), Some(args)) => Ok(Self {
+    fn build_from_toml(
+        toml: question::toml::Test,
+        dir_path: &str,
+        timeout: Duration,
+        compare: Compare,
+    ) -> Result<Self, QuestionError> {
+        let cases = case::build_cases(toml.args, toml.cases, dir_path, timeout, compare)?;
+        let generator = toml
+            .generator
+            .map(|generator| Generator::build_from_toml(generator, dir_path, timeout, compare))
+            .transpose()?;
+        // Generated cases are enough on their own
+        let cases = match (cases, &generator) {
+            (None, Some(_)) => Some(Vec::new()),
+            (cases, _) => cases,
+        };
+        match (toml.binary, cases) {
+            (Some(binary), Some(cases)) => Ok(Self {
                 binary: format!("{}/{}", dir_path, binary),
-                args,
+                cases,
+                generator,
+                checks: ExtraChecks::build_from_toml(toml.leak_check, toml.sanitize),
             }),
-            _ => Err(MissingKeys::Exec),
+            _ => Err(MissingKeys::Exec.into()),
         }
     }
 
@@ -69,48 +180,90 @@ impl Exec {
         &self,
         submission: &Submission,
         dirs: &QuestionDirs,
+        config: &Config,
     ) -> Result<TestResult, QuestionError> {
+        let cases = with_generated(&self.cases, self.generator.as_ref())?;
         match submission {
-            // TODO: Add a check here to confirm the binary file exists
-            Submission::Exec(exec) => self.run_with_binary(exec.name()),
+            Submission::Exec(exec) => {
+                let binary = exec.path(dirs);
+                let result_val = self.run_with_binary(Program::Binary(&binary), &cases);
+                self.checks.run(result_val, &binary, None, &cases, config)
+            }
+            Submission::Script(script) => {
+                let path = script.path(dirs);
+                self.run_with_binary(script.program(&path), &cases)
+            }
             Submission::Sources(sources) => {
                 let mut compiler = Compiler::new(sources.compiler());
-                for source in sources.sources().iter() {
-                    compiler.add_source(format!("{}/{}", dirs.submit_directory, source));
+                for source in sources.paths(dirs) {
+                    compiler.add_source(source);
                 }
                 if let Some(flags) = sources.flags() {
                     for flag in flags.iter() {
                         compiler.add_flag(flag);
                     }
                 }
-                let compile_result = compiler.compile()?;
+                let compile_result = sources.compile(&compiler, dirs)?;
                 let binary = match compile_result {
                     CompileResult::Ok(binary_name) => binary_name,
                     CompileResult::Err(error) => return Ok(TestResult::Failed(error)),
                 };
                 let binary = format!("./{}", binary);
-                let result_val = self.run_with_binary(&binary);
+                let result_val = self.run_with_binary(Program::Binary(&binary), &cases);
+                let result_val =
+                    self.checks
+                        .run(result_val, &binary, Some(&compiler), &cases, config);
                 remove_binary(&binary)?;
                 result_val
             }
+            Submission::Makefile(makefile) => {
+                let build = match makefile.build(dirs)? {
+                    BuildResult::Ok(build) => build,
+                    BuildResult::Err(error) => return Ok(TestResult::Failed(error)),
+                };
+                let binary = build.artifact();
+                let result_val = self.run_with_binary(Program::Binary(&binary), &cases);
+                let result_val = self.checks.run(result_val, &binary, None, &cases, config);
+                build.remove()?;
+                result_val
+            }
+            Submission::Library(_) => Err(QuestionError::InvalidTestType(String::from(
+                "Executable cannot be run with a library submission",
+            ))),
         }
     }
 
-    fn run_with_binary(&self, binary: &str) -> Result<TestResult, QuestionError> {
+    fn run_with_binary(
+        &self,
+        submission: Program,
+        cases: &[Case],
+    ) -> Result<TestResult, QuestionError> {
         let mut trace = Trace::new();
-        for args in self.args.iter() {
-            let test_output = match run_binary_with_args(&self.binary, args)? {
+        for case in cases.iter() {
+            let test_output = match run_binary(&self.binary, case)? {
                 BinaryResult::Output(output) => output,
-                BinaryResult::Timeout => panic!("A questions test timed out, question is invalid"),
+                BinaryResult::Timeout => return Err(test_timeout(case)),
             };
-            let submit_output = match run_binary_with_args(binary, args)? {
+            let submit_output = match submission.run(case)? {
                 BinaryResult::Output(output) => output,
                 BinaryResult::Timeout => {
                     return Ok(TestResult::Failed(TestError::Timeout));
                 }
             };
-            if test_output != submit_output {
-                trace.binary_output(args, test_output, submit_output);
+            if test_output.signal() != submit_output.signal() {
+                if let Some(crash) = TestError::crash(case, &submit_output) {
+                    return Ok(TestResult::Failed(crash));
+                }
+            }
+            if !case.expected.matches(&submit_output) {
+                trace.case_failure(case, &submit_output);
+            }
+            if !case
+                .expected
+                .compare
+                .outputs_match(&test_output, &submit_output)
+            {
+                trace.binary_output(case, test_output, submit_output);
             }
         }
         if trace.exists() {
@@ -127,10 +280,16 @@ pub struct UnitTest {
     sources: Vec<String>,
     flags: Option<Vec<String>>,
     framework: Option<String>,
+    timeout: Duration,
+    checks: ExtraChecks,
 }
 
 impl UnitTest {
-    fn build_from_toml(toml: question::toml::Test, dir_path: &str) -> Result<Self, MissingKeys> {
+    fn build_from_toml(
+        toml: question::toml::Test,
+        dir_path: &str,
+        timeout: Duration,
+    ) -> Result<Self, MissingKeys> {
         match (toml.compiler, toml.sources) {
             (Some(compiler), Some(sources)) => Ok(Self {
                 compiler,
@@ -140,6 +299,8 @@ impl UnitTest {
                     .collect(),
                 flags: toml.flags,
                 framework: toml.framework,
+                timeout,
+                checks: ExtraChecks::build_from_toml(toml.leak_check, toml.sanitize),
             }),
             _ => Err(MissingKeys::UnitTest),
         }
@@ -147,7 +308,7 @@ impl UnitTest {
 
     fn invalid_framework(&self, config: &crate::config::Config) -> Result<(), String> {
         if let Some(test_framework) = &self.framework {
-            if config.get_framework(&test_framework).is_none() {
+            if config.get_framework(test_framework).is_none() {
                 return Err(test_framework.clone());
             } else {
                 return Ok(());
@@ -164,30 +325,59 @@ impl UnitTest {
     ) -> Result<TestResult, QuestionError> {
         match submission {
             Submission::Sources(sources) => {
-                let compile_result = self.compile_binary(sources, dirs, config)?;
-                let binary = match compile_result {
-                    CompileResult::Ok(binary_name) => format!("./{}", binary_name),
-                    CompileResult::Err(error) => return Ok(TestResult::Failed(error)),
-                };
-                let result_val = self.run_with_binary(&binary);
-                remove_binary(&binary)?;
-                result_val
+                let compiler = self.compiler(sources.paths(dirs), config);
+                let compile_result = sources.compile(&compiler, dirs)?;
+                self.run_compiled(compile_result, Some(&compiler), config)
             }
+            Submission::Makefile(makefile) => self.run_linked(makefile.build(dirs)?, config),
+            Submission::Library(library) => self.run_linked(library.build(dirs)?, config),
             _ => Err(QuestionError::InvalidTestType(String::from(
-                "Unit test cannot be run with any submission type other than sources",
+                "Unit test cannot be run with any submission type other than sources, makefile or library",
             ))),
         }
     }
 
-    fn compile_binary(
+    /// Link the unit test against the library that was built, if it was
+    fn run_linked(&self, build: BuildResult, config: &Config) -> Result<TestResult, QuestionError> {
+        let build = match build {
+            BuildResult::Ok(build) => build,
+            BuildResult::Err(error) => return Ok(TestResult::Failed(error)),
+        };
+        let mut compiler = self.compiler(Vec::new(), config);
+        compiler.add_source(build.artifact());
+        let result_val = compiler
+            .compile()
+            .and_then(|compile_result| self.run_compiled(compile_result, None, config));
+        build.remove()?;
+        result_val
+    }
+
+    /// Run the unit test built by `compile_result`, then remove it. `compiler` is given if the
+    /// submission may be rebuilt with sanitizers.
+    fn run_compiled(
         &self,
-        sources: &crate::question::submission::Sources,
-        dirs: &QuestionDirs,
+        compile_result: CompileResult,
+        compiler: Option<&Compiler>,
         config: &Config,
-    ) -> Result<CompileResult, QuestionError> {
+    ) -> Result<TestResult, QuestionError> {
+        let binary = match compile_result {
+            CompileResult::Ok(binary_name) => format!("./{}", binary_name),
+            CompileResult::Err(error) => return Ok(TestResult::Failed(error)),
+        };
+        let result_val = self.run_with_binary(&binary);
+        let case = Case::empty(self.timeout);
+        let result_val = self
+            .checks
+            .run(result_val, &binary, compiler, [&case], config);
+        remove_binary(&binary)?;
+        result_val
+    }
+
+    /// A [`Compiler`] for the unit test, given the paths of the `submitted` sources
+    fn compiler<'a>(&'a self, submitted: Vec<String>, config: &'a Config) -> Compiler<'a> {
         let mut compiler = Compiler::new(&self.compiler);
-        for source in sources.sources().iter() {
-            compiler.add_source(format!("{}/{}", dirs.submit_directory, source));
+        for source in submitted {
+            compiler.add_source(source);
         }
         for source in self.sources.iter() {
             compiler.add_source(source.clone());
@@ -198,21 +388,23 @@ impl UnitTest {
             }
         }
         if let Some(framework_name) = &self.framework {
-            let framework_flags = config.get_framework(framework_name).unwrap();
-            for flag in framework_flags.iter() {
+            let framework = config.get_framework(framework_name).unwrap();
+            for flag in framework.flags() {
                 compiler.add_flag(flag);
             }
         }
-        compiler.compile()
+        compiler
     }
 
     fn run_with_binary(&self, binary: &str) -> Result<TestResult, QuestionError> {
         let mut trace = Trace::new();
-        let dummy_args: [String; 0] = [];
-        let output = match run_binary_with_args(binary, &dummy_args)? {
+        let output = match run_binary(binary, &Case::empty(self.timeout))? {
             BinaryResult::Output(output) => output,
             BinaryResult::Timeout => return Ok(TestResult::Failed(TestError::Timeout)),
         };
+        if let Some(crash) = TestError::crash(&Case::empty(self.timeout), &output) {
+            return Ok(TestResult::Failed(crash));
+        }
         if output.code() != 0 {
             trace.unit_test_output(output);
         }
@@ -228,23 +420,42 @@ impl UnitTest {
 pub struct Sources {
     compiler: String,
     sources: Vec<String>,
-    args: Vec<Vec<String>>,
+    cases: Vec<Case>,
+    generator: Option<Generator>,
     flags: Option<Vec<String>>,
+    checks: ExtraChecks,
 }
 
 impl Sources {
-    fn build_from_toml(toml: question::toml::Test, dir_path: &str) -> Result<Self, MissingKeys> {
-        match (toml.compiler, toml.sources, toml.args) {
-            (Some(compiler), Some(sources), Some(args)) => Ok(Self {
+    fn build_from_toml(
+        toml: question::toml::Test,
+        dir_path: &str,
+        timeout: Duration,
+        compare: Compare,
+    ) -> Result<Self, QuestionError> {
+        let cases = case::build_cases(toml.args, toml.cases, dir_path, timeout, compare)?;
+        let generator = toml
+            .generator
+            .map(|generator| Generator::build_from_toml(generator, dir_path, timeout, compare))
+            .transpose()?;
+        // Generated cases are enough on their own
+        let cases = match (cases, &generator) {
+            (None, Some(_)) => Some(Vec::new()),
+            (cases, _) => cases,
+        };
+        match (toml.compiler, toml.sources, cases) {
+            (Some(compiler), Some(sources), Some(cases)) => Ok(Self {
                 compiler,
                 sources: sources
                     .into_iter()
                     .map(|elem| format!("{}/{}", dir_path, elem))
                     .collect(),
-                args,
+                cases,
+                generator,
                 flags: toml.flags,
+                checks: ExtraChecks::build_from_toml(toml.leak_check, toml.sanitize),
             }),
-            _ => Err(MissingKeys::Sources),
+            _ => Err(MissingKeys::Sources.into()),
         }
     }
 
@@ -252,22 +463,58 @@ impl Sources {
         &self,
         submission: &Submission,
         dirs: &QuestionDirs,
+        config: &Config,
     ) -> Result<TestResult, QuestionError> {
+        let cases = with_generated(&self.cases, self.generator.as_ref())?;
         match submission {
             Submission::Exec(exec) => {
+                let binary = exec.path(dirs);
+                let test_binary = self.compile_test_binary()?;
+                let return_val =
+                    self.run_with_binaries(&test_binary, Program::Binary(&binary), &cases);
+                let return_val = self.checks.run(return_val, &binary, None, &cases, config);
+                remove_binary(&test_binary)?;
+                return_val
+            }
+            Submission::Makefile(makefile) => {
+                let build = match makefile.build(dirs)? {
+                    BuildResult::Ok(build) => build,
+                    BuildResult::Err(error) => return Ok(TestResult::Failed(error)),
+                };
+                let binary = build.artifact();
+                let return_val = self.compile_test_binary().and_then(|test_binary| {
+                    let return_val =
+                        self.run_with_binaries(&test_binary, Program::Binary(&binary), &cases);
+                    let return_val = self.checks.run(return_val, &binary, None, &cases, config);
+                    remove_binary(&test_binary)?;
+                    return_val
+                });
+                build.remove()?;
+                return_val
+            }
+            Submission::Library(_) => Err(QuestionError::InvalidTestType(String::from(
+                "Sources cannot be run with a library submission",
+            ))),
+            Submission::Script(script) => {
+                let path = script.path(dirs);
                 let test_binary = self.compile_test_binary()?;
-                let return_val = self.run_with_binaries(&test_binary, exec.name());
+                let return_val =
+                    self.run_with_binaries(&test_binary, script.program(&path), &cases);
                 remove_binary(&test_binary)?;
                 return_val
             }
             Submission::Sources(sources) => {
-                let compile_result = self.compile_submit_binary(sources, dirs)?;
-                let submit_binary = match compile_result {
+                let compiler = self.submit_compiler(sources, dirs);
+                let submit_binary = match sources.compile(&compiler, dirs)? {
                     CompileResult::Ok(binary_name) => format!("./{}", binary_name),
                     CompileResult::Err(error) => return Ok(TestResult::Failed(error)),
                 };
                 let test_binary = self.compile_test_binary()?;
-                let return_val = self.run_with_binaries(&test_binary, &submit_binary);
+                let return_val =
+                    self.run_with_binaries(&test_binary, Program::Binary(&submit_binary), &cases);
+                let return_val =
+                    self.checks
+                        .run(return_val, &submit_binary, Some(&compiler), &cases, config);
                 remove_binary(&test_binary)?;
                 remove_binary(&submit_binary)?;
                 return_val
@@ -275,21 +522,21 @@ impl Sources {
         }
     }
 
-    fn compile_submit_binary(
-        &self,
-        sources: &crate::question::submission::Sources,
+    fn submit_compiler<'a>(
+        &'a self,
+        sources: &'a crate::question::submission::Sources,
         dirs: &QuestionDirs,
-    ) -> Result<CompileResult, QuestionError> {
+    ) -> Compiler<'a> {
         let mut compiler = Compiler::new(&self.compiler);
-        for source in sources.sources().iter() {
-            compiler.add_source(format!("{}/{}", dirs.submit_directory, source));
+        for source in sources.paths(dirs) {
+            compiler.add_source(source);
         }
         if let Some(flags) = &sources.flags() {
             for flag in flags.iter() {
                 compiler.add_flag(flag);
             }
         }
-        compiler.compile()
+        compiler
     }
 
     fn compile_test_binary(&self) -> Result<String, QuestionError> {
@@ -315,20 +562,33 @@ impl Sources {
     fn run_with_binaries(
         &self,
         test_binary: &str,
-        submit_binary: &str,
+        submission: Program,
+        cases: &[Case],
     ) -> Result<TestResult, QuestionError> {
         let mut trace = Trace::new();
-        for args in self.args.iter() {
-            let test_output = match run_binary_with_args(test_binary, args)? {
+        for case in cases.iter() {
+            let test_output = match run_binary(test_binary, case)? {
                 BinaryResult::Output(output) => output,
-                BinaryResult::Timeout => panic!("A question's test timed out, question is invalid"),
+                BinaryResult::Timeout => return Err(test_timeout(case)),
             };
-            let submit_output = match run_binary_with_args(submit_binary, args)? {
+            let submit_output = match submission.run(case)? {
                 BinaryResult::Output(output) => output,
                 BinaryResult::Timeout => return Ok(TestResult::Failed(TestError::Timeout)),
             };
-            if test_output != submit_output {
-                trace.binary_output(args, test_output, submit_output);
+            if test_output.signal() != submit_output.signal() {
+                if let Some(crash) = TestError::crash(case, &submit_output) {
+                    return Ok(TestResult::Failed(crash));
+                }
+            }
+            if !case.expected.matches(&submit_output) {
+                trace.case_failure(case, &submit_output);
+            }
+            if !case
+                .expected
+                .compare
+                .outputs_match(&test_output, &submit_output)
+            {
+                trace.binary_output(case, test_output, submit_output);
             }
         }
         if trace.exists() {
@@ -344,24 +604,48 @@ pub struct CompiledTogether {
     compiler: String,
     flags: Option<Vec<String>>,
     sources: Vec<String>,
-    stdout_file: String,
-    stderr_file: String,
-    args: Vec<Vec<String>>,
+    /// Files the combined output of every case is compared against. Without them, each case is
+    /// checked against its own expected output.
+    output_files: Option<(String, String)>,
+    compare: Compare,
+    cases: Vec<Case>,
+    checks: ExtraChecks,
 }
 
 impl CompiledTogether {
-    fn build_from_toml(toml: question::toml::Test, dir_path: &str) -> Result<Self, QuestionError> {
-        match (
-            toml.compiler,
-            toml.sources,
-            toml.expected_stdout,
-            toml.expected_stderr,
-            toml.args,
-        ) {
-            (Some(compiler), Some(sources), Some(stdout_file), Some(stderr_file), Some(args)) => {
+    fn build_from_toml(
+        toml: question::toml::Test,
+        dir_path: &str,
+        timeout: Duration,
+        compare: Compare,
+    ) -> Result<Self, QuestionError> {
+        let output_files = match (toml.expected_stdout, toml.expected_stderr) {
+            (Some(stdout_file), Some(stderr_file)) => {
                 let stdout_file = format!("{}/{}", dir_path, stdout_file);
                 let stderr_file = format!("{}/{}", dir_path, stderr_file);
                 Self::validate_output_files(&stdout_file, &stderr_file)?;
+                compare.validate(&fs::read_to_string(&stdout_file)?)?;
+                compare.validate(&fs::read_to_string(&stderr_file)?)?;
+                Some((stdout_file, stderr_file))
+            }
+            (None, None) => None,
+            _ => return Err(MissingKeys::CompiledTogether.into()),
+        };
+        let cases = case::build_cases(toml.args, toml.cases, dir_path, timeout, compare)?;
+        match (toml.compiler, toml.sources, cases) {
+            (Some(compiler), Some(sources), Some(mut cases)) => {
+                for case in cases.iter_mut() {
+                    case.expected.exit_code.get_or_insert(0);
+                    if output_files.is_none() {
+                        // Output that a case does not mention is expected to be empty
+                        case.expected
+                            .stdout
+                            .get_or_insert_with(|| vec![String::new()]);
+                        case.expected
+                            .stderr
+                            .get_or_insert_with(|| vec![String::new()]);
+                    }
+                }
                 Ok(Self {
                     compiler,
                     flags: toml.flags,
@@ -369,9 +653,10 @@ impl CompiledTogether {
                         .into_iter()
                         .map(|elem| format!("{}/{}", dir_path, elem))
                         .collect(),
-                    stdout_file,
-                    stderr_file,
-                    args,
+                    output_files,
+                    compare,
+                    cases,
+                    checks: ExtraChecks::build_from_toml(toml.leak_check, toml.sanitize),
                 })
             }
             _ => Err(MissingKeys::CompiledTogether.into()),
@@ -396,32 +681,62 @@ impl CompiledTogether {
         &self,
         submission: &Submission,
         dirs: &QuestionDirs,
+        config: &Config,
     ) -> Result<TestResult, QuestionError> {
         match submission {
             Submission::Sources(sources) => {
-                let compile_result = self.compile_binary(sources, dirs)?;
-                let binary = match compile_result {
-                    CompileResult::Ok(binary_name) => format!("./{}", binary_name),
-                    CompileResult::Err(error) => return Ok(TestResult::Failed(error)),
-                };
-                let result_val = self.run_with_binary(&binary);
-                remove_binary(&binary)?;
-                result_val
+                let compiler = self.compiler(sources.paths(dirs));
+                let compile_result = sources.compile(&compiler, dirs)?;
+                self.run_compiled(compile_result, Some(&compiler), config)
             }
+            Submission::Makefile(makefile) => self.run_linked(makefile.build(dirs)?, config),
+            Submission::Library(library) => self.run_linked(library.build(dirs)?, config),
             _ => Err(QuestionError::InvalidTestType(String::from(
-                "Expected Output cannot be run with any submission type other than sources",
+                "Expected Output cannot be run with any submission type other than sources, makefile or library",
             ))),
         }
     }
 
-    fn compile_binary(
+    /// Link the test sources against the library that was built, if it was
+    fn run_linked(&self, build: BuildResult, config: &Config) -> Result<TestResult, QuestionError> {
+        let build = match build {
+            BuildResult::Ok(build) => build,
+            BuildResult::Err(error) => return Ok(TestResult::Failed(error)),
+        };
+        let mut compiler = self.compiler(Vec::new());
+        compiler.add_source(build.artifact());
+        let result_val = compiler
+            .compile()
+            .and_then(|compile_result| self.run_compiled(compile_result, None, config));
+        build.remove()?;
+        result_val
+    }
+
+    /// Run the binary built by `compile_result`, then remove it. `compiler` is given if the
+    /// submission may be rebuilt with sanitizers.
+    fn run_compiled(
         &self,
-        sources: &crate::question::submission::Sources,
-        dirs: &QuestionDirs,
-    ) -> Result<CompileResult, QuestionError> {
+        compile_result: CompileResult,
+        compiler: Option<&Compiler>,
+        config: &Config,
+    ) -> Result<TestResult, QuestionError> {
+        let binary = match compile_result {
+            CompileResult::Ok(binary_name) => format!("./{}", binary_name),
+            CompileResult::Err(error) => return Ok(TestResult::Failed(error)),
+        };
+        let result_val = self.run_with_binary(&binary);
+        let result_val = self
+            .checks
+            .run(result_val, &binary, compiler, &self.cases, config);
+        remove_binary(&binary)?;
+        result_val
+    }
+
+    /// A [`Compiler`] for the test sources, given the paths of the `submitted` sources
+    fn compiler(&self, submitted: Vec<String>) -> Compiler<'_> {
         let mut compiler = Compiler::new(&self.compiler);
-        for source in sources.sources().iter() {
-            compiler.add_source(format!("{}/{}", dirs.submit_directory, source));
+        for source in submitted {
+            compiler.add_source(source);
         }
         for source in self.sources.iter() {
             compiler.add_source(source.clone());
@@ -431,25 +746,74 @@ impl CompiledTogether {
                 compiler.add_flag(flag);
             }
         }
-        compiler.compile()
+        compiler
     }
 
     fn run_with_binary(&self, binary: &str) -> Result<TestResult, QuestionError> {
+        match &self.output_files {
+            Some((stdout_file, stderr_file)) => {
+                self.run_against_files(binary, stdout_file, stderr_file)
+            }
+            None => self.run_cases(binary),
+        }
+    }
+
+    fn run_cases(&self, binary: &str) -> Result<TestResult, QuestionError> {
         let mut trace = Trace::new();
-        let expected_out = fs::read_to_string(&self.stdout_file)?;
-        let expected_err = fs::read_to_string(&self.stderr_file)?;
+        let mut passed = 0;
+        for case in self.cases.iter() {
+            let output = match run_binary(binary, case)? {
+                BinaryResult::Output(output) => output,
+                BinaryResult::Timeout => return Ok(TestResult::Failed(TestError::Timeout)),
+            };
+            if let Some(crash) = TestError::crash(case, &output) {
+                return Ok(TestResult::Failed(crash));
+            }
+            if case.expected.matches(&output) {
+                passed += 1;
+            } else {
+                trace.case_failure(case, &output);
+            }
+        }
+        if trace.exists() {
+            trace.custom_message(&format!("{} of {} cases passed", passed, self.cases.len()));
+            Ok(TestResult::Failed(TestError::IncorrectOutput(trace)))
+        } else {
+            Ok(TestResult::Passed)
+        }
+    }
+
+    fn run_against_files(
+        &self,
+        binary: &str,
+        stdout_file: &str,
+        stderr_file: &str,
+    ) -> Result<TestResult, QuestionError> {
+        let mut trace = Trace::new();
+        let expected_out = fs::read_to_string(stdout_file)?;
+        let expected_err = fs::read_to_string(stderr_file)?;
         let mut actual_out = String::new();
         let mut actual_err = String::new();
-        for args in self.args.iter() {
-            let output = match run_binary_with_args(binary, args)? {
+        for case in self.cases.iter() {
+            let output = match run_binary(binary, case)? {
                 BinaryResult::Output(output) => output,
                 BinaryResult::Timeout => return Ok(TestResult::Failed(TestError::Timeout)),
             };
+            if let Some(crash) = TestError::crash(case, &output) {
+                return Ok(TestResult::Failed(crash));
+            }
+            if !case.expected.matches(&output) {
+                trace.case_failure(case, &output);
+            }
             actual_out.push_str(output.stdout());
             actual_err.push_str(output.stderr());
         }
-        if actual_out != expected_out || actual_err != expected_err {
+        if !self.compare.matches(&expected_out, &actual_out)
+            || !self.compare.matches(&expected_err, &actual_err)
+        {
             trace.file_outputs((expected_out, expected_err), (actual_out, actual_err));
+        }
+        if trace.exists() {
             Ok(TestResult::Failed(TestError::IncorrectOutput(trace)))
         } else {
             Ok(TestResult::Passed)
@@ -457,25 +821,531 @@ impl CompiledTogether {
     }
 }
 
+#[derive(Debug)]
+pub struct Function {
+    compiler: String,
+    flags: Option<Vec<String>>,
+    prototype: Prototype,
+    includes: Vec<String>,
+    /// Each call, with the [`Case`] used to run it in the harness
+    calls: Vec<(Call, Case)>,
+    tolerance: f64,
+    checks: ExtraChecks,
+}
+
+impl Function {
+    fn build_from_toml(
+        toml: question::toml::Test,
+        dir_path: &str,
+        timeout: Duration,
+        compare: Compare,
+    ) -> Result<Self, QuestionError> {
+        let (compiler, prototype, calls) = match (toml.compiler, toml.prototype, toml.calls) {
+            (Some(compiler), Some(prototype), Some(calls)) => (compiler, prototype, calls),
+            _ => return Err(MissingKeys::Function.into()),
+        };
+        let prototype = Prototype::parse(&prototype)?;
+        let calls = calls
+            .into_iter()
+            .enumerate()
+            .map(|(index, call)| {
+                Self::build_call(call, index, &prototype, dir_path, timeout, compare)
+            })
+            .collect::<Result<_, _>>()?;
+        let tolerance = match compare {
+            Compare::Numeric { tolerance } => tolerance,
+            _ => 0.0,
+        };
+        Ok(Self {
+            compiler,
+            flags: toml.flags,
+            prototype,
+            includes: toml.includes.unwrap_or_default(),
+            calls,
+            tolerance,
+            checks: ExtraChecks::build_from_toml(toml.leak_check, toml.sanitize),
+        })
+    }
+
+    /// Build the `index`th call. The harness is told which call to make by its only argument.
+    fn build_call(
+        toml: question::toml::Call,
+        index: usize,
+        prototype: &Prototype,
+        dir_path: &str,
+        timeout: Duration,
+        compare: Compare,
+    ) -> Result<(Call, Case), QuestionError> {
+        let returns = match toml.returns {
+            None => None,
+            Some(_) if prototype.kind() == ReturnType::Void => {
+                return Err(QuestionError::InvalidCase(format!(
+                    "{} returns void, so calls cannot have a return value",
+                    prototype.name()
+                )))
+            }
+            Some(toml_parse::Value::String(expression)) => Some(expression),
+            Some(toml_parse::Value::Integer(integer)) => Some(integer.to_string()),
+            Some(toml_parse::Value::Float(float)) => Some(format!("{:?}", float)),
+            Some(toml_parse::Value::Boolean(boolean)) => Some(boolean.to_string()),
+            Some(invalid) => {
+                return Err(QuestionError::InvalidCase(format!(
+                    "return value must be a number, a boolean or a C expression: {}",
+                    invalid
+                )))
+            }
+        };
+        let call = Call {
+            args: toml.args,
+            returns,
+        };
+        let case = question::toml::Case {
+            name: Some(call.expression(prototype)),
+            args: vec![index.to_string()],
+            timeout: toml.timeout,
+            stdout: toml.stdout,
+            stdout_file: toml.stdout_file,
+            ..Default::default()
+        };
+        let case = Case::build_from_toml(case, index, dir_path, timeout, compare)?;
+        Ok((call, case))
+    }
+
+    fn run(
+        &self,
+        submission: &Submission,
+        dirs: &QuestionDirs,
+        config: &Config,
+    ) -> Result<TestResult, QuestionError> {
+        match submission {
+            Submission::Sources(sources) => {
+                // Kept until the submission has been built with sanitizers, if it is
+                let harness = self.write_harness()?;
+                let compiler = self.compiler(sources.paths(dirs), &harness);
+                let result_val = sources.compile(&compiler, dirs).and_then(|compile_result| {
+                    self.run_compiled(compile_result, Some(&compiler), config)
+                });
+                fs::remove_file(&harness)?;
+                result_val
+            }
+            Submission::Makefile(makefile) => self.run_linked(makefile.build(dirs)?, config),
+            Submission::Library(library) => self.run_linked(library.build(dirs)?, config),
+            _ => Err(QuestionError::InvalidTestType(String::from(
+                "Function cannot be run with any submission type other than sources, makefile or library",
+            ))),
+        }
+    }
+
+    /// Link the harness against the library that was built, if it was
+    fn run_linked(&self, build: BuildResult, config: &Config) -> Result<TestResult, QuestionError> {
+        let build = match build {
+            BuildResult::Ok(build) => build,
+            BuildResult::Err(error) => return Ok(TestResult::Failed(error)),
+        };
+        let result_val = self.write_harness().and_then(|harness| {
+            let mut compiler = self.compiler(Vec::new(), &harness);
+            compiler.add_source(build.artifact());
+            let result_val = compiler
+                .compile()
+                .and_then(|compile_result| self.run_compiled(compile_result, None, config));
+            fs::remove_file(&harness)?;
+            result_val
+        });
+        build.remove()?;
+        result_val
+    }
+
+    /// Write the generated harness to a temporary file, returning its path
+    fn write_harness(&self) -> Result<PathBuf, QuestionError> {
+        let calls: Vec<Call> = self.calls.iter().map(|(call, _)| call.clone()).collect();
+        let source =
+            function::generate_harness(&self.prototype, &calls, &self.includes, self.tolerance);
+        let name: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(10)
+            .map(char::from)
+            .collect();
+        let path = std::env::temp_dir().join(format!("examtrainer-harness-{}.c", name));
+        fs::write(&path, source)?;
+        Ok(path)
+    }
+
+    /// Run the harness built by `compile_result`, then remove it. `compiler` is given if the
+    /// submission may be rebuilt with sanitizers.
+    fn run_compiled(
+        &self,
+        compile_result: CompileResult,
+        compiler: Option<&Compiler>,
+        config: &Config,
+    ) -> Result<TestResult, QuestionError> {
+        let binary = match compile_result {
+            CompileResult::Ok(binary_name) => format!("./{}", binary_name),
+            CompileResult::Err(error) => return Ok(TestResult::Failed(error)),
+        };
+        let result_val = self.run_with_binary(&binary);
+        let cases = self.calls.iter().map(|(_, case)| case);
+        let result_val = self
+            .checks
+            .run(result_val, &binary, compiler, cases, config);
+        remove_binary(&binary)?;
+        result_val
+    }
+
+    /// A [`Compiler`] for the `harness`, given the paths of the `submitted` sources
+    fn compiler(&self, submitted: Vec<String>, harness: &Path) -> Compiler<'_> {
+        let mut compiler = Compiler::new(&self.compiler);
+        for source in submitted {
+            compiler.add_source(source);
+        }
+        compiler.add_source(harness.display().to_string());
+        if let Some(flags) = &self.flags {
+            for flag in flags.iter() {
+                compiler.add_flag(flag);
+            }
+        }
+        compiler
+    }
+
+    fn run_with_binary(&self, binary: &str) -> Result<TestResult, QuestionError> {
+        let mut trace = Trace::new();
+        let mut passed = 0;
+        for (call, case) in self.calls.iter() {
+            let output = match run_binary(binary, case)? {
+                BinaryResult::Output(output) => output,
+                BinaryResult::Timeout => return Ok(TestResult::Failed(TestError::Timeout)),
+            };
+            if let Some(signal) = output.signal() {
+                return Ok(TestResult::Failed(TestError::Crashed {
+                    signal,
+                    args: call.args.clone(),
+                }));
+            }
+            if output.code() == 0 && case.expected.stdout_matches(output.stdout()) {
+                passed += 1;
+            } else {
+                trace.call_failure(case, &output);
+            }
+        }
+        if trace.exists() {
+            trace.custom_message(&format!("{} of {} calls passed", passed, self.calls.len()));
+            Ok(TestResult::Failed(TestError::IncorrectOutput(trace)))
+        } else {
+            Ok(TestResult::Passed)
+        }
+    }
+}
+
+/// Leak checkers run binaries much more slowly, so each case may take this many times as long
+const LEAK_CHECK_SLOWDOWN: u32 = 10;
+
+/// Binaries built with sanitizers run more slowly, so each case may take this many times as long
+const SANITIZER_SLOWDOWN: u32 = 3;
+
+#[derive(Debug)]
+pub struct Filesystem {
+    binary: String,
+    /// Directory copied into the working directory of every run, unless a case gives its own
+    initial_tree: Option<String>,
+    cases: Vec<Case>,
+}
+
+impl Filesystem {
+    fn build_from_toml(
+        toml: question::toml::Test,
+        dir_path: &str,
+        timeout: Duration,
+        compare: Compare,
+    ) -> Result<Self, QuestionError> {
+        let cases = match case::build_cases(toml.args, toml.cases, dir_path, timeout, compare)? {
+            Some(cases) => cases,
+            // A single run without arguments
+            None => vec![Case::build_from_toml(
+                Default::default(),
+                0,
+                dir_path,
+                timeout,
+                compare,
+            )?],
+        };
+        let initial_tree = toml
+            .initial_tree
+            .map(|tree| format!("{}/{}", dir_path, tree));
+        if let Some(tree) = &initial_tree {
+            if !Path::new(tree).is_dir() {
+                return Err(QuestionError::InvalidTestType(format!(
+                    "initial_tree '{}' is not a directory",
+                    tree
+                )));
+            }
+        }
+        match toml.binary {
+            Some(binary) => Ok(Self {
+                binary: format!("{}/{}", dir_path, binary),
+                initial_tree,
+                cases,
+            }),
+            None => Err(MissingKeys::Filesystem.into()),
+        }
+    }
+
+    fn run(
+        &self,
+        submission: &Submission,
+        dirs: &QuestionDirs,
+    ) -> Result<TestResult, QuestionError> {
+        match submission {
+            Submission::Exec(exec) => {
+                let binary = exec.path(dirs);
+                self.run_with_program(Program::Binary(&binary))
+            }
+            Submission::Script(script) => {
+                let path = script.path(dirs);
+                self.run_with_program(script.program(&path))
+            }
+            _ => Err(QuestionError::InvalidTestType(String::from(
+                "Filesystem can only be run with an executable or script submission",
+            ))),
+        }
+    }
+
+    fn run_with_program(&self, submission: Program) -> Result<TestResult, QuestionError> {
+        let mut trace = Trace::new();
+        for case in self.cases.iter() {
+            let initial_tree = case.initial_tree.as_ref().or(self.initial_tree.as_ref());
+            let expected = Workspace::new(initial_tree.map(String::as_str))?;
+            let actual = Workspace::new(initial_tree.map(String::as_str))?;
+            let result = Self::run_case(
+                case,
+                Program::Binary(&self.binary),
+                &expected,
+                submission,
+                &actual,
+                &mut trace,
+            );
+            expected.remove()?;
+            actual.remove()?;
+            if let Some(error) = result? {
+                return Ok(TestResult::Failed(error));
+            }
+        }
+        if trace.exists() {
+            Ok(TestResult::Failed(TestError::IncorrectOutput(trace)))
+        } else {
+            Ok(TestResult::Passed)
+        }
+    }
+
+    /// Run `case` with the test and the submission, each in their own [`Workspace`], recording
+    /// any difference in output or in the files left behind in `trace`
+    fn run_case(
+        case: &Case,
+        test: Program,
+        expected: &Workspace,
+        submission: Program,
+        actual: &Workspace,
+        trace: &mut Trace,
+    ) -> Result<Option<TestError>, QuestionError> {
+        let test_output = match test.run_in(case, expected.dir())? {
+            BinaryResult::Output(output) => output,
+            BinaryResult::Timeout => return Err(test_timeout(case)),
+        };
+        let submit_output = match submission.run_in(case, actual.dir())? {
+            BinaryResult::Output(output) => output,
+            BinaryResult::Timeout => return Ok(Some(TestError::Timeout)),
+        };
+        if test_output.signal() != submit_output.signal() {
+            if let Some(crash) = TestError::crash(case, &submit_output) {
+                return Ok(Some(crash));
+            }
+        }
+        if !case.expected.matches(&submit_output) {
+            trace.case_failure(case, &submit_output);
+        }
+        if !case
+            .expected
+            .compare
+            .outputs_match(&test_output, &submit_output)
+        {
+            trace.binary_output(case, test_output, submit_output);
+        }
+        let differences = Tree::differences(&expected.tree()?, &actual.tree()?);
+        if !differences.is_empty() {
+            trace.tree_differences(case, &differences);
+        }
+        Ok(None)
+    }
+}
+
+/// Checks run again on a submission once it has passed a test
+#[derive(Debug, Clone, Copy)]
+struct ExtraChecks {
+    leak_check: bool,
+    /// Whether to rebuild the submission with sanitizers, following `Config` if [`None`]
+    sanitize: Option<bool>,
+}
+
+impl ExtraChecks {
+    fn build_from_toml(leak_check: Option<bool>, sanitize: Option<bool>) -> Self {
+        Self {
+            leak_check: leak_check.unwrap_or(false),
+            sanitize,
+        }
+    }
+
+    /// Run the extra checks on `binary`, if its test gave `result` and it passed. `compiler`
+    /// built `binary`, and is [`None`] if the submission was not compiled by `examtrainer`, in
+    /// which case it is not rebuilt with sanitizers.
+    fn run<'a>(
+        &self,
+        result: Result<TestResult, QuestionError>,
+        binary: &str,
+        compiler: Option<&Compiler>,
+        cases: impl IntoIterator<Item = &'a Case> + Clone,
+        config: &Config,
+    ) -> Result<TestResult, QuestionError> {
+        if !matches!(result, Ok(TestResult::Passed)) {
+            return result;
+        }
+        if self.leak_check {
+            let result = check_leaks(binary, cases.clone(), config)?;
+            if !result.is_passed() {
+                return Ok(result);
+            }
+        }
+        match compiler {
+            Some(compiler) if self.sanitize.unwrap_or(config.sanitize()) => {
+                check_sanitized(compiler, cases)
+            }
+            _ => Ok(TestResult::Passed),
+        }
+    }
+}
+
+/// Rerun `binary` for each of `cases` under the leak checker in `config`, failing with
+/// [`TestError::MemoryLeak`] on the first case that leaks
+fn check_leaks<'a>(
+    binary: &str,
+    cases: impl IntoIterator<Item = &'a Case>,
+    config: &Config,
+) -> Result<TestResult, QuestionError> {
+    let checker = config.leak_checker();
+    let program = Program::Checked { checker, binary };
+    for case in cases {
+        let case = Case {
+            timeout: case.timeout * LEAK_CHECK_SLOWDOWN,
+            ..case.clone()
+        };
+        let output = match program.run(&case)? {
+            BinaryResult::Output(output) => output,
+            BinaryResult::Timeout => return Ok(TestResult::Failed(TestError::Timeout)),
+        };
+        if output.code() == checker.exit_code() {
+            let mut trace = Trace::new();
+            trace.leak_report(&case, &output);
+            return Ok(TestResult::Failed(TestError::MemoryLeak(trace)));
+        }
+    }
+    Ok(TestResult::Passed)
+}
+
+/// The error for the question's own program timing out on `case`, which is a problem with the
+/// question rather than the submission. A generated case gives its seed, so it can be reproduced.
+fn test_timeout(case: &Case) -> QuestionError {
+    let mut description = format!("{}, with args {:?}", case.name, case.args);
+    if let Some(seed) = case.seed {
+        description += &format!(", generated from seed {}", seed);
+    }
+    QuestionError::TestTimeout(description)
+}
+
+/// The fixed `cases` of a test, followed by new ones from its `generator` if it has one
+fn with_generated(
+    cases: &[Case],
+    generator: Option<&Generator>,
+) -> Result<Vec<Case>, QuestionError> {
+    let mut cases = cases.to_vec();
+    if let Some(generator) = generator {
+        cases.extend(generator.cases()?);
+    }
+    Ok(cases)
+}
+
+/// Build the submission again with `compiler` and sanitizers, and run each of `cases` against it,
+/// failing with [`TestError::UndefinedBehaviour`] on the first sanitizer report
+fn check_sanitized<'a>(
+    compiler: &Compiler,
+    cases: impl IntoIterator<Item = &'a Case>,
+) -> Result<TestResult, QuestionError> {
+    let binary = match compiler.with_flags(&sanitizer::FLAGS).compile()? {
+        CompileResult::Ok(binary_name) => format!("./{}", binary_name),
+        CompileResult::Err(error) => return Ok(TestResult::Failed(error)),
+    };
+    let result = run_sanitized(&binary, cases);
+    remove_binary(&binary)?;
+    result
+}
+
+fn run_sanitized<'a>(
+    binary: &str,
+    cases: impl IntoIterator<Item = &'a Case>,
+) -> Result<TestResult, QuestionError> {
+    for case in cases {
+        let case = Case {
+            timeout: case.timeout * SANITIZER_SLOWDOWN,
+            ..case.clone()
+        };
+        let output = match run_binary(binary, &case)? {
+            BinaryResult::Output(output) => output,
+            BinaryResult::Timeout => return Ok(TestResult::Failed(TestError::Timeout)),
+        };
+        if let Some(report) = sanitizer::report(output.stderr()) {
+            let mut trace = Trace::new();
+            trace.sanitizer_report(&case, &report);
+            return Ok(TestResult::Failed(TestError::UndefinedBehaviour(trace)));
+        }
+    }
+    Ok(TestResult::Passed)
+}
+
 #[derive(Debug)]
 pub enum Test {
     Exec(Exec),
     UnitTest(UnitTest),
     Sources(Sources),
     CompiledTogether(CompiledTogether),
+    Function(Function),
+    Filesystem(Filesystem),
 }
 
 impl Test {
+    /// Build a [`Test`] from the `[test]` table of a Question .toml file, where every run of a
+    /// binary may take `default_timeout`, unless the table gives its own `timeout`.
     pub fn build_from_toml(
         toml: question::toml::Test,
         dir_path: &str,
+        default_timeout: Duration,
     ) -> Result<Self, QuestionError> {
+        let timeout = case::parse_timeout(toml.timeout, default_timeout)?;
+        let compare =
+            Compare::build_from_toml(toml.compare.as_deref(), toml.tolerance, Compare::Exact)?;
         match &toml.test_type[..] {
-            "executable" => Ok(Self::Exec(Exec::build_from_toml(toml, dir_path)?)),
-            "unit-test" => Ok(Self::UnitTest(UnitTest::build_from_toml(toml, dir_path)?)),
-            "sources" => Ok(Self::Sources(Sources::build_from_toml(toml, dir_path)?)),
+            "executable" => Ok(Self::Exec(Exec::build_from_toml(
+                toml, dir_path, timeout, compare,
+            )?)),
+            "unit-test" => Ok(Self::UnitTest(UnitTest::build_from_toml(
+                toml, dir_path, timeout,
+            )?)),
+            "sources" => Ok(Self::Sources(Sources::build_from_toml(
+                toml, dir_path, timeout, compare,
+            )?)),
             "expected-output" => Ok(Self::CompiledTogether(CompiledTogether::build_from_toml(
-                toml, dir_path,
+                toml, dir_path, timeout, compare,
+            )?)),
+            "function" => Ok(Self::Function(Function::build_from_toml(
+                toml, dir_path, timeout, compare,
+            )?)),
+            "filesystem" => Ok(Self::Filesystem(Filesystem::build_from_toml(
+                toml, dir_path, timeout, compare,
             )?)),
             invalid => Err(QuestionError::InvalidTestType(invalid.into())),
         }
@@ -488,10 +1358,26 @@ impl Test {
         config: &Config,
     ) -> Result<TestResult, QuestionError> {
         match self {
-            Self::Exec(exec) => exec.run(submission, dirs),
+            Self::Exec(exec) => exec.run(submission, dirs, config),
             Self::UnitTest(unit_test) => unit_test.run(submission, dirs, config),
-            Self::Sources(sources) => sources.run(submission, dirs),
-            Self::CompiledTogether(compiled_together) => compiled_together.run(submission, dirs),
+            Self::Sources(sources) => sources.run(submission, dirs, config),
+            Self::CompiledTogether(compiled_together) => {
+                compiled_together.run(submission, dirs, config)
+            }
+            Self::Function(function) => function.run(submission, dirs, config),
+            Self::Filesystem(filesystem) => filesystem.run(submission, dirs),
+        }
+    }
+
+    /// Is the submission rerun under the leak checker once it passes?
+    pub fn leak_check(&self) -> bool {
+        match self {
+            Self::Exec(exec) => exec.checks.leak_check,
+            Self::UnitTest(unit_test) => unit_test.checks.leak_check,
+            Self::Sources(sources) => sources.checks.leak_check,
+            Self::CompiledTogether(compiled_together) => compiled_together.checks.leak_check,
+            Self::Function(function) => function.checks.leak_check,
+            Self::Filesystem(_) => false,
         }
     }
 
@@ -501,6 +1387,25 @@ impl Test {
             _ => Ok(()),
         }
     }
+
+    /// The compiler used to build the tests, if any
+    pub fn compiler(&self) -> Option<&str> {
+        match self {
+            Self::Exec(_) | Self::Filesystem(_) => None,
+            Self::UnitTest(unit_test) => Some(&unit_test.compiler),
+            Self::Sources(sources) => Some(&sources.compiler),
+            Self::CompiledTogether(compiled_together) => Some(&compiled_together.compiler),
+            Self::Function(function) => Some(&function.compiler),
+        }
+    }
+
+    /// The unit-test framework the tests are linked against, if any
+    pub fn framework(&self) -> Option<&str> {
+        match self {
+            Self::UnitTest(unit_test) => unit_test.framework.as_deref(),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -509,9 +1414,16 @@ pub enum TestResult {
     Failed(TestError),
 }
 
+impl TestResult {
+    pub fn is_passed(&self) -> bool {
+        matches!(self, Self::Passed)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::DEFAULT_TIMEOUT;
     use crate::question::toml;
     use std::fs;
     #[test]
@@ -520,34 +1432,80 @@ mod tests {
         let dir_path = String::from("tst/resources/questions/hello_world");
         let question_toml: toml::Question = toml_parse::from_str(&buffer)?;
         let test_toml: toml::Test = question_toml.test;
-        let test: Test = Test::build_from_toml(test_toml, &dir_path)?;
+        let test: Test = Test::build_from_toml(test_toml, &dir_path, DEFAULT_TIMEOUT)?;
         assert!(matches!(test, Test::CompiledTogether(_)));
-        match test {
-            Test::CompiledTogether(test) => {
-                assert_eq!(test.compiler, "gcc");
-                assert_eq!(
-                    test.flags,
-                    Some(vec!("-Wall".into(), "-Wextra".into(), "-Werror".into()))
-                );
-                assert_eq!(
-                    test.sources,
-                    vec!("tst/resources/questions/hello_world/main.c")
-                );
-                assert_eq!(
-                    test.stdout_file,
-                    "tst/resources/questions/hello_world/hello_world.out"
-                );
-                assert_eq!(
-                    test.stderr_file,
-                    "tst/resources/questions/hello_world/hello_world.err"
-                );
-            }
-            _ => (),
+        if let Test::CompiledTogether(test) = test {
+            assert_eq!(test.compiler, "gcc");
+            assert_eq!(
+                test.flags,
+                Some(vec!("-Wall".into(), "-Wextra".into(), "-Werror".into()))
+            );
+            assert_eq!(
+                test.sources,
+                vec!("tst/resources/questions/hello_world/main.c")
+            );
+            assert_eq!(
+                test.output_files,
+                Some((
+                    "tst/resources/questions/hello_world/hello_world.out".into(),
+                    "tst/resources/questions/hello_world/hello_world.err".into()
+                ))
+            );
         }
 
         Ok(())
     }
 
+    #[test]
+    fn missing_initial_tree() -> Result<(), QuestionError> {
+        let dir_path = "tst/resources/questions_filesystem/ex00";
+        let buffer = fs::read_to_string(format!("{}/ex00.toml", dir_path))?;
+        let mut test_toml = toml_parse::from_str::<toml::Question>(&buffer)?.test;
+        test_toml.initial_tree = Some("strat".into());
+        let test = Test::build_from_toml(test_toml, dir_path, DEFAULT_TIMEOUT);
+        assert!(matches!(test, Err(QuestionError::InvalidTestType(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_program_timeout() -> Result<(), QuestionError> {
+        let config = Config::new_from("tst/resources/test_config1.toml").unwrap();
+        let buffer = fs::read_to_string("tst/resources/questions/ft_countdown/ft_countdown.toml")?;
+        let dir_path = String::from("tst/resources/questions/ft_countdown");
+        let question_toml: toml::Question = toml_parse::from_str(&buffer)?;
+        let dirs = QuestionDirs {
+            submit_directory: "tst/resources/rendu_test/ft_countdown".into(),
+            subject_directory: "tst/resources/questions/ft_countdown/ft_countdown.subject".into(),
+            question_directory: "tst/resources/questions/ft_countdown".into(),
+        };
+        let timeout = Duration::from_nanos(1);
+        let test = Test::build_from_toml(question_toml.test, &dir_path, timeout)?;
+        let submission = Submission::build_from_toml(question_toml.submission, &config)?;
+        match test.run(&submission, &dirs, &config) {
+            Err(QuestionError::TestTimeout(case)) => assert_eq!(case, "case 1, with args []"),
+            result => panic!(
+                "The question's own program should time out, not {:?}",
+                result
+            ),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn generated_test_timeout() {
+        let case = Case {
+            name: "random case 3".into(),
+            args: vec!["4".into(), "-2".into()],
+            seed: Some(42),
+            ..Case::empty(DEFAULT_TIMEOUT)
+        };
+        assert_eq!(
+            test_timeout(&case).to_string(),
+            "The question's own program timed out in random case 3, with args [\"4\", \"-2\"], \
+             generated from seed 42, so the question is invalid"
+        );
+    }
+
     #[test]
     fn run_passing_test_exec() -> Result<(), QuestionError> {
         let config = Config::new_from("tst/resources/test_config2.toml").unwrap();
@@ -561,8 +1519,8 @@ mod tests {
         };
         let test_toml: question::toml::Test = question_toml.test;
         let submission_toml: question::toml::Submission = question_toml.submission;
-        let test: Test = Test::build_from_toml(test_toml, &dir_path)?;
-        let submission: Submission = Submission::build_from_toml(submission_toml)?;
+        let test: Test = Test::build_from_toml(test_toml, &dir_path, DEFAULT_TIMEOUT)?;
+        let submission: Submission = Submission::build_from_toml(submission_toml, &config)?;
         let test_result = test.run(&submission, &dirs, &config)?;
         assert!(matches!(test_result, TestResult::Passed));
         Ok(())
@@ -581,8 +1539,8 @@ mod tests {
         };
         let test_toml: toml::Test = question_toml.test;
         let submission_toml: toml::Submission = question_toml.submission;
-        let test: Test = Test::build_from_toml(test_toml, &dir_path)?;
-        let submission: Submission = Submission::build_from_toml(submission_toml)?;
+        let test: Test = Test::build_from_toml(test_toml, &dir_path, DEFAULT_TIMEOUT)?;
+        let submission: Submission = Submission::build_from_toml(submission_toml, &config)?;
         let test_result = test.run(&submission, &dirs, &config)?;
         let error = match test_result {
             TestResult::Passed => panic!("Test should have failed"),
@@ -5,6 +5,7 @@
 //! sophisticated structs.
 
 use serde::Deserialize;
+use std::collections::BTreeMap;
 
 #[derive(Deserialize, Debug)]
 pub struct Info {
@@ -19,8 +20,108 @@ pub struct Submission {
     pub submission_type: String,
     pub sources: Option<Vec<String>>,
     pub binary: Option<String>,
+    /// The static library built by a makefile submission, instead of `binary`
+    pub library: Option<String>,
+    /// Targets of a makefile submission that must work
+    pub targets: Option<Vec<String>>,
+    /// The only symbols a library may define, other than static ones
+    pub exports: Option<Vec<String>>,
     pub compiler: Option<String>,
+    pub toolchain: Option<String>,
     pub flags: Option<Vec<String>>,
+    /// Library functions a sources submission may call, any others are forbidden
+    pub allowed_functions: Option<Vec<String>>,
+    pub forbidden_functions: Option<Vec<String>>,
+    /// Files that may be submitted as well as those required
+    pub allowed_files: Option<Vec<String>>,
+    pub script: Option<String>,
+    /// The name of an interpreter in `Config`, used to run `script`
+    pub interpreter: Option<String>,
+}
+
+/// A single string, or a list of them
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::One(one) => vec![one],
+            Self::Many(many) => many,
+        }
+    }
+}
+
+/// A single test case, from `[[test.cases]]` or a table in `args`
+///
+/// Input and expected output can each be given directly (`stdin`, `stdout`, `stderr`), or as a
+/// file in the question directory (`stdin_file`, `stdout_file`, `stderr_file`). Expected output
+/// can also be a list, any of which is accepted.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+pub struct Case {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub timeout: Option<f64>,
+    pub stdin: Option<String>,
+    pub stdin_file: Option<String>,
+    pub stdout: Option<OneOrMany>,
+    pub stdout_file: Option<OneOrMany>,
+    pub stderr: Option<OneOrMany>,
+    pub stderr_file: Option<OneOrMany>,
+    pub exit_code: Option<i32>,
+    pub compare: Option<String>,
+    pub tolerance: Option<f64>,
+    /// The contents of each file the program should write in its working directory
+    pub files: Option<BTreeMap<String, String>>,
+    /// Directory copied into the working directory before the program runs
+    pub initial_tree: Option<String>,
+}
+
+/// A single call to the function under test in a 'function' test, from `[[test.calls]]`
+///
+/// `args` are C expressions, and `returns` is either a number, a boolean or a C expression.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+pub struct Call {
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub returns: Option<toml_parse::Value>,
+    pub stdout: Option<OneOrMany>,
+    pub stdout_file: Option<OneOrMany>,
+    pub timeout: Option<f64>,
+}
+
+/// A single entry of `args`: either a plain list of arguments, or a [`Case`] table
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ArgSet {
+    Args(Vec<String>),
+    Table(Box<Case>),
+}
+
+/// Random arguments for an 'executable' or 'sources' test, from `[test.generator]`
+///
+/// Either a `program` in the question directory, or a built-in `kind`: "ints", between `min` and
+/// `max`, "strings" of `charset`, or "words" from `words`. Lengths are in characters for strings,
+/// and in words for sentences of words.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+pub struct Generator {
+    pub program: Option<String>,
+    pub kind: Option<String>,
+    /// Number of cases generated each time a submission is graded
+    pub count: Option<usize>,
+    pub min_args: Option<usize>,
+    pub max_args: Option<usize>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub charset: Option<String>,
+    pub words: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -29,12 +130,30 @@ pub struct Test {
     pub subject: String,
     pub sources: Option<Vec<String>>,
     pub compiler: Option<String>,
+    pub toolchain: Option<String>,
     pub flags: Option<Vec<String>>,
     pub binary: Option<String>,
-    pub args: Option<Vec<Vec<String>>>,
+    pub args: Option<Vec<ArgSet>>,
+    pub cases: Option<Vec<Case>>,
+    pub generator: Option<Generator>,
     pub expected_stdout: Option<String>,
     pub expected_stderr: Option<String>,
     pub framework: Option<String>,
+    /// Seconds each run of a binary may take, overriding the default in `Config`
+    pub timeout: Option<f64>,
+    pub compare: Option<String>,
+    pub tolerance: Option<f64>,
+    /// The prototype of the function tested by a 'function' test
+    pub prototype: Option<String>,
+    /// Extra headers included by the harness of a 'function' test
+    pub includes: Option<Vec<String>>,
+    pub calls: Option<Vec<Call>>,
+    /// Directory copied into the working directory of each run in a 'filesystem' test
+    pub initial_tree: Option<String>,
+    /// Rerun the submission under the leak checker in `Config` once it passes
+    pub leak_check: Option<bool>,
+    /// Rebuild the submission with sanitizers once it passes, overriding `Config`
+    pub sanitize: Option<bool>,
 }
 
 /// A PDS used as a target for parsing of Question .toml files
@@ -59,7 +178,7 @@ mod tests {
         let toml: Question = toml_parse::from_str(&buffer)?;
         assert_eq!(toml.info.name, "hello_world");
         assert_eq!(toml.info.authors, Some(vec!("Ryan Lucas".into())));
-        assert_eq!(toml.info.difficulty, 2);
+        assert_eq!(toml.info.difficulty, Some(2));
         assert_eq!(toml.submission.submission_type, "sources");
         assert_eq!(toml.submission.sources, Some(vec!("hello_world.c".into())));
         assert_eq!(toml.test.test_type, "expected-output");
@@ -74,16 +193,16 @@ mod tests {
         assert_eq!(
             toml.test.args,
             Some(vec!(
-                Vec::new(),
-                vec!("Ryan".into(), "Lucas".into()),
-                vec!(
+                ArgSet::Args(Vec::new()),
+                ArgSet::Args(vec!("Ryan".into(), "Lucas".into())),
+                ArgSet::Args(vec!(
                     "did".into(),
                     "you".into(),
                     "know".into(),
                     "shinigami".into(),
                     "love".into(),
                     "apples".into()
-                )
+                ))
             ))
         );
         Ok(())
@@ -1,3 +1,6 @@
+use crate::question::case::Case;
+use crate::question::compare::Compare;
+use crate::question::tree::Difference;
 use crate::utils::ProgramOutput;
 use std::fmt;
 
@@ -17,24 +20,121 @@ impl Trace {
         !self.data.is_empty()
     }
 
-    pub fn binary_output(
-        &mut self,
-        args: &[String],
-        expected: ProgramOutput,
-        actual: ProgramOutput,
-    ) {
-        self.data += "Failure with args: ";
-        for arg in args.iter() {
+    fn case_header(&mut self, case: &Case) {
+        for arg in case.args.iter() {
             self.data += arg;
             self.data += ", ";
         }
         self.data += "\n";
+        if let Some(seed) = case.seed {
+            self.data += &format!("Generated from seed: {}\n", seed);
+        }
+        if let Some(stdin) = &case.stdin {
+            self.data += "With stdin:\n";
+            self.push_line(stdin);
+        }
+    }
+
+    fn push_line(&mut self, text: &str) {
+        self.data += text;
+        if !text.ends_with('\n') {
+            self.data += "\n";
+        }
+    }
+
+    pub fn binary_output(&mut self, case: &Case, expected: ProgramOutput, actual: ProgramOutput) {
+        self.data += "Failure with args: ";
+        self.case_header(case);
         self.data += "Expected Output:\n";
         self.data += &expected.to_string();
         self.data += "Actual Output:\n";
         self.data += &actual.to_string();
     }
 
+    /// Record the parts of `actual` that do not match what `case` expects
+    pub fn case_failure(&mut self, case: &Case, actual: &ProgramOutput) {
+        self.data += &format!("Failure in {}, with args: ", case.name);
+        self.case_header(case);
+        let expected = &case.expected;
+        if expected.compare != Compare::Exact {
+            self.data += &format!("Compared as: {}\n", expected.compare);
+        }
+        if let Some(stdout) = expected.stdout.as_deref() {
+            if !expected.stdout_matches(actual.stdout()) {
+                self.accepted_outputs("Stdout", stdout);
+                self.data += "Actual Stdout:\n";
+                self.push_line(actual.stdout());
+            }
+        }
+        if let Some(stderr) = expected.stderr.as_deref() {
+            if !expected.stderr_matches(actual.stderr()) {
+                self.accepted_outputs("Stderr", stderr);
+                self.data += "Actual Stderr:\n";
+                self.push_line(actual.stderr());
+            }
+        }
+        if let Some(code) = expected.exit_code {
+            if !expected.exit_code_matches(actual.code()) {
+                self.data += &format!("Expected Exit Code: {}\n", code);
+                self.data += &format!("Actual Exit Code: {}\n", actual.code());
+            }
+        }
+        for difference in expected.file_differences(actual).iter() {
+            self.push_line(&difference.to_string());
+        }
+    }
+
+    /// Record a failed call in a 'function' test, where `actual` is the output of the harness
+    pub fn call_failure(&mut self, case: &Case, actual: &ProgramOutput) {
+        self.data += &format!("Failure in {}\n", case.name);
+        if actual.code() != 0 {
+            let reason = actual.stderr().lines().last().unwrap_or_default();
+            self.data += &format!("Wrong return value: {}\n", reason);
+        }
+        let expected = &case.expected;
+        if let Some(stdout) = expected.stdout.as_deref() {
+            if !expected.stdout_matches(actual.stdout()) {
+                self.accepted_outputs("Stdout", stdout);
+                self.data += "Actual Stdout:\n";
+                self.push_line(actual.stdout());
+            }
+        }
+    }
+
+    fn accepted_outputs(&mut self, stream: &str, accepted: &[String]) {
+        self.data += &format!("Expected {}:\n", stream);
+        for (index, output) in accepted.iter().enumerate() {
+            if index > 0 {
+                self.data += "Or:\n";
+            }
+            self.push_line(output);
+        }
+    }
+
+    /// Record the report of a leak checker, which found a leak in the run of `case`
+    pub fn leak_report(&mut self, case: &Case, checker_output: &ProgramOutput) {
+        self.data += "Memory leak with args: ";
+        self.case_header(case);
+        self.data += "Leak Checker Output:\n";
+        self.push_line(checker_output.stderr());
+    }
+
+    /// Record the start of a sanitizer `report`, from the run of `case`
+    pub fn sanitizer_report(&mut self, case: &Case, report: &str) {
+        self.data += "Undefined behaviour with args: ";
+        self.case_header(case);
+        self.push_line(report);
+    }
+
+    /// Record each way in which the files left by the run of `case` differ from those expected
+    pub fn tree_differences(&mut self, case: &Case, differences: &[Difference]) {
+        self.data += "Wrong files with args: ";
+        self.case_header(case);
+        for difference in differences.iter() {
+            self.push_line(&difference.to_string());
+        }
+    }
+
     pub fn unit_test_output(&mut self, output: ProgramOutput) {
         self.data += "Unit Test failed. Output:\n";
         self.data += &output.to_string();
@@ -85,14 +185,17 @@ mod tests {
     #[test]
     fn basic_trace() -> Result<(), Error> {
         let mut trace = Trace::new();
-        let args = vec!["i_dont_exist.txt".to_owned(), "-e".to_owned()];
+        let case = Case {
+            args: vec!["i_dont_exist.txt".to_owned(), "-e".to_owned()],
+            ..Case::empty(std::time::Duration::from_secs(10))
+        };
         let mut exec = Command::new("cat");
-        for arg in args.iter() {
+        for arg in case.args.iter() {
             exec.arg(arg);
         }
         let output: ProgramOutput = exec.output()?.into();
         assert!(output.code() != 0);
-        trace.binary_output(&args, output.clone(), output);
+        trace.binary_output(&case, output.clone(), output);
         assert_eq!(
             trace.to_string(),
             format!(
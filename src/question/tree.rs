@@ -0,0 +1,371 @@
+//! Directory trees left behind by a submission
+//!
+//! A [`Workspace`] is a fresh temporary directory that a submission is run in, seeded with a copy
+//! of a directory from the question. Once the submission has run, the [`Tree`] it left behind is
+//! read back, and compared with the tree left by the question's own program. Each file is
+//! compared by its type, permissions, and its contents or the target of a symlink.
+
+use crate::question::compiler::temp_dir;
+use crate::question::QuestionError;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A temporary directory for a single run of a program
+#[derive(Debug)]
+pub struct Workspace {
+    dir: PathBuf,
+}
+
+impl Workspace {
+    /// Create an empty temporary directory, holding a copy of `seed` if it is given
+    pub fn new(seed: Option<&str>) -> Result<Self, QuestionError> {
+        let workspace = Self {
+            dir: temp_dir("workspace")?,
+        };
+        if let Some(seed) = seed {
+            if let Err(error) = copy_directory(seed, &workspace.dir) {
+                workspace.remove()?;
+                return Err(error);
+            }
+        }
+        Ok(workspace)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn tree(&self) -> Result<Tree, QuestionError> {
+        Tree::read(&self.dir)
+    }
+
+    /// Remove the directory, even if the program took away its own permissions on parts of it
+    pub fn remove(self) -> Result<(), QuestionError> {
+        Command::new("chmod")
+            .arg("-R")
+            .arg("u+rwx")
+            .arg(&self.dir)
+            .output()?;
+        fs::remove_dir_all(&self.dir)?;
+        Ok(())
+    }
+}
+
+/// Copy the contents of the directory `from` into the directory `to`
+pub fn copy_directory(from: &str, to: &Path) -> Result<(), QuestionError> {
+    let output = Command::new("cp")
+        .arg("-r")
+        .arg(format!("{}/.", from))
+        .arg(to)
+        .output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "could not copy {}: {}",
+            from,
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ))
+        .into())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Entry {
+    /// A regular file, with no contents if they could not be read
+    File {
+        mode: u32,
+        contents: Option<Vec<u8>>,
+    },
+    Directory {
+        mode: u32,
+    },
+    Symlink(PathBuf),
+}
+
+impl Entry {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::File { .. } => "file",
+            Self::Directory { .. } => "directory",
+            Self::Symlink(_) => "symlink",
+        }
+    }
+}
+
+/// Every file under a directory, by its path relative to that directory
+#[derive(Debug, PartialEq)]
+pub struct Tree {
+    entries: BTreeMap<String, Entry>,
+}
+
+impl Tree {
+    pub fn read(dir: &Path) -> Result<Self, QuestionError> {
+        let mut tree = Self {
+            entries: BTreeMap::new(),
+        };
+        tree.read_children(dir, Path::new(""))?;
+        Ok(tree)
+    }
+
+    /// Add everything under `dir`, which is at `relative` in the tree. A directory the program
+    /// made unreadable is kept, without its children.
+    fn read_children(&mut self, dir: &Path, relative: &Path) -> Result<(), QuestionError> {
+        let children = match fs::read_dir(dir) {
+            Ok(children) => children,
+            Err(_) => return Ok(()),
+        };
+        for child in children {
+            let child = child?;
+            let path = child.path();
+            let relative = relative.join(child.file_name());
+            let metadata = fs::symlink_metadata(&path)?;
+            let mode = metadata.permissions().mode() & 0o7777;
+            let entry = if metadata.file_type().is_symlink() {
+                Entry::Symlink(fs::read_link(&path)?)
+            } else if metadata.is_dir() {
+                self.read_children(&path, &relative)?;
+                Entry::Directory { mode }
+            } else {
+                Entry::File {
+                    mode,
+                    contents: fs::read(&path).ok(),
+                }
+            };
+            self.entries.insert(relative.display().to_string(), entry);
+        }
+        Ok(())
+    }
+
+    /// Every way in which `actual` differs from `expected`, in order of path
+    pub fn differences(expected: &Self, actual: &Self) -> Vec<Difference> {
+        let mut paths: Vec<&String> = expected.entries.keys().collect();
+        paths.extend(actual.entries.keys());
+        paths.sort();
+        paths.dedup();
+        paths
+            .into_iter()
+            .flat_map(|path| {
+                let (expected, actual) = (expected.entries.get(path), actual.entries.get(path));
+                Difference::between(path, expected, actual)
+            })
+            .collect()
+    }
+}
+
+/// One way in which a file differs from the expected one
+#[derive(Debug, PartialEq)]
+pub enum Difference {
+    Missing(String),
+    Unexpected(String),
+    Kind {
+        path: String,
+        expected: &'static str,
+        actual: &'static str,
+    },
+    Permissions {
+        path: String,
+        expected: u32,
+        actual: u32,
+    },
+    Contents {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    Target {
+        path: String,
+        expected: PathBuf,
+        actual: PathBuf,
+    },
+}
+
+impl Difference {
+    fn between(path: &str, expected: Option<&Entry>, actual: Option<&Entry>) -> Vec<Self> {
+        let path = path.to_string();
+        let (expected, actual) = match (expected, actual) {
+            (Some(expected), Some(actual)) => (expected, actual),
+            (Some(_), None) => return vec![Self::Missing(path)],
+            (None, Some(_)) => return vec![Self::Unexpected(path)],
+            (None, None) => return Vec::new(),
+        };
+        let mut differences = Vec::new();
+        match (expected, actual) {
+            (
+                Entry::File {
+                    mode: expected_mode,
+                    contents: expected,
+                },
+                Entry::File {
+                    mode: actual_mode,
+                    contents: actual,
+                },
+            ) => {
+                differences.extend(Self::permissions(&path, *expected_mode, *actual_mode));
+                if expected != actual {
+                    differences.push(Self::Contents {
+                        path,
+                        expected: text(expected),
+                        actual: text(actual),
+                    });
+                }
+            }
+            (Entry::Directory { mode: expected }, Entry::Directory { mode: actual }) => {
+                differences.extend(Self::permissions(&path, *expected, *actual));
+            }
+            (Entry::Symlink(expected), Entry::Symlink(actual)) => {
+                if expected != actual {
+                    differences.push(Self::Target {
+                        path,
+                        expected: expected.clone(),
+                        actual: actual.clone(),
+                    });
+                }
+            }
+            (expected, actual) => differences.push(Self::Kind {
+                path,
+                expected: expected.kind(),
+                actual: actual.kind(),
+            }),
+        }
+        differences
+    }
+
+    fn permissions(path: &str, expected: u32, actual: u32) -> Option<Self> {
+        (expected != actual).then(|| Self::Permissions {
+            path: path.to_string(),
+            expected,
+            actual,
+        })
+    }
+}
+
+fn text(contents: &Option<Vec<u8>>) -> String {
+    match contents {
+        Some(contents) => String::from_utf8_lossy(contents).into_owned(),
+        None => String::from("(unreadable)"),
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Missing(path) => write!(f, "Missing: {}", path),
+            Self::Unexpected(path) => write!(f, "Unexpected: {}", path),
+            Self::Kind {
+                path,
+                expected,
+                actual,
+            } => write!(f, "{} should be a {}, not a {}", path, expected, actual),
+            Self::Permissions {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} should have permissions {:04o}, not {:04o}",
+                path, expected, actual
+            ),
+            Self::Contents {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Wrong contents in {}\nExpected:\n{}\nActual:\n{}",
+                path, expected, actual
+            ),
+            Self::Target {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} should link to {}, not {}",
+                path,
+                expected.display(),
+                actual.display()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    fn create(dir: &Path, path: &str, contents: &str, mode: u32) -> Result<(), QuestionError> {
+        let path = dir.join(path);
+        fs::write(&path, contents)?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode))?;
+        Ok(())
+    }
+
+    #[test]
+    fn tree_differences() -> Result<(), QuestionError> {
+        let (expected, actual) = (Workspace::new(None)?, Workspace::new(None)?);
+        for workspace in [&expected, &actual] {
+            fs::create_dir(workspace.dir().join("dir"))?;
+            create(workspace.dir(), "dir/same", "same\n", 0o644)?;
+        }
+        create(expected.dir(), "missing", "", 0o644)?;
+        create(actual.dir(), "unexpected", "", 0o644)?;
+        create(expected.dir(), "mode", "", 0o455)?;
+        create(actual.dir(), "mode", "42\n", 0o644)?;
+        create(expected.dir(), "contents", "42\n", 0o644)?;
+        create(actual.dir(), "contents", "24\n", 0o644)?;
+        symlink("dir/same", expected.dir().join("link"))?;
+        symlink("contents", actual.dir().join("link"))?;
+        fs::create_dir(expected.dir().join("kind"))?;
+        create(actual.dir(), "kind", "", 0o644)?;
+        let differences = Tree::differences(&expected.tree()?, &actual.tree()?);
+        let differences: Vec<String> = differences.iter().map(|diff| diff.to_string()).collect();
+        assert_eq!(
+            differences,
+            vec![
+                "Wrong contents in contents\nExpected:\n42\n\nActual:\n24\n",
+                "kind should be a directory, not a file",
+                "link should link to dir/same, not contents",
+                "Missing: missing",
+                "mode should have permissions 0455, not 0644",
+                "Wrong contents in mode\nExpected:\n\nActual:\n42\n",
+                "Unexpected: unexpected",
+            ]
+        );
+        assert_eq!(Tree::differences(&expected.tree()?, &expected.tree()?), []);
+        expected.remove()?;
+        actual.remove()?;
+        Ok(())
+    }
+
+    #[test]
+    fn seeded_workspace() -> Result<(), QuestionError> {
+        let seed = "tst/resources/questions_filesystem/ex00/start";
+        let workspace = Workspace::new(Some(seed))?;
+        assert_eq!(workspace.tree()?, Tree::read(Path::new(seed))?);
+        fs::set_permissions(workspace.dir(), fs::Permissions::from_mode(0o500))?;
+        let dir = workspace.dir().to_path_buf();
+        workspace.remove()?;
+        assert!(!dir.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_seed() {
+        let seed = "tst/resources/questions_filesystem/ex00/missing";
+        match Workspace::new(Some(seed)) {
+            Err(QuestionError::IO(error)) => {
+                assert!(error
+                    .to_string()
+                    .starts_with(&format!("could not copy {}: ", seed)))
+            }
+            result => panic!("Copying a missing directory should fail, not {:?}", result),
+        }
+    }
+}
@@ -114,12 +114,9 @@ fn exam_loop<'a>(
         input = super::read_input()?;
 
         // Check to see if the exam has timed out
-        match thread_receiver.try_recv() {
-            Ok(_) => {
-                output::print_timeout();
-                break;
-            }
-            Err(_) => (),
+        if thread_receiver.try_recv().is_ok() {
+            output::print_timeout();
+            break;
         }
 
         match &input[..] {
@@ -155,11 +152,11 @@ fn assign_new_question<'a>(
     exam: &Exam,
     questions: &'a QuestionDB,
 ) -> Result<bool, Error> {
-    if let Some(next_question_name) = exam.select_question(&user) {
+    if let Some(next_question_name) = exam.select_question(user) {
         let question = questions
             .get_question_by_name(next_question_name)
             .ok_or(Error::General("Question not found".to_string()))?;
-        let points = exam.get_points(&user);
+        let points = exam.get_points(user);
         user.assign_question(question, points)?;
         question.create_directories(config)?;
         Ok(false)
@@ -2,10 +2,13 @@ mod exam;
 pub mod main_menu;
 mod single_question;
 
+use crate::cli::Command;
 use crate::config::Config;
+use crate::exam::ExamDB;
 use crate::output;
 use crate::question::test::TestError;
 use crate::question::test::TestResult;
+use crate::question::QuestionDB;
 use crate::user::User;
 use crate::utils::timestamp;
 use crate::Error;
@@ -19,12 +22,51 @@ enum YesNoAnswer {
 
 use YesNoAnswer::{No, Yes};
 
+/// Run a single [`Command`] given on the command line, without entering the main menu.
+///
+/// Returns whether the command passed, which is only `false` for a failed `grade`, or a `doctor`
+/// that found a problem.
+pub fn run_command(
+    config: Config,
+    questions: QuestionDB,
+    exams: ExamDB,
+    command: Command,
+) -> Result<bool, Error> {
+    match command {
+        Command::ListQuestions => print!("{}", questions),
+        Command::ListExams => print!("{}", exams),
+        Command::Init => crate::init(&config)?,
+        Command::Config => output::print_config_info(&config),
+        Command::Doctor => {
+            let checks = crate::doctor::run(&config, &questions);
+            output::doctor_report(&checks);
+            return Ok(checks.iter().all(|check| check.is_passed()));
+        }
+        Command::Help => output::usage(),
+        Command::Question(name) => {
+            create_standard_directories(&config)?;
+            single_question::run(&config, &name, &questions)?
+        }
+        Command::Exam(name) => {
+            create_standard_directories(&config)?;
+            exam::run(&config, &name, &questions, &exams)?
+        }
+        Command::Grade(name) => {
+            let test_result = crate::grade(&config, &questions, &name)?;
+            output::grade_report(&name, &test_result);
+            return Ok(test_result.is_passed());
+        }
+        Command::MainMenu => main_menu::run(config, questions, exams)?,
+    }
+    Ok(true)
+}
+
 fn read_input() -> Result<String, Error> {
     let mut buffer = String::new();
     let stdin = io::stdin();
 
     stdin.read_line(&mut buffer)?;
-    let new_line = buffer.find(|c: char| c == '\n');
+    let new_line = buffer.find('\n');
     if let Some(trim_point) = new_line {
         buffer.truncate(trim_point);
     }
@@ -45,7 +87,7 @@ fn wait_for_enter() {
     io::stdin().read_exact(&mut [0]).unwrap();
 }
 
-fn create_standard_directories(config: &Config) -> Result<(), Error> {
+pub fn create_standard_directories(config: &Config) -> Result<(), Error> {
     std::process::Command::new("mkdir")
         .arg("-p")
         .arg(config.subject_dir())
@@ -59,10 +59,7 @@ fn exit(config: &Config, question: &Question) -> Result<YesNoAnswer, Error> {
             println!("\nWould you like to delete the subject & answer directories? (y/n)? ");
             println!("The following directories would be deleted:");
             println!("- {}", question.directories().submit_directory);
-            println!(
-                "- {}",
-                format!("{}/{}", config.subject_dir(), question.name())
-            );
+            println!("- {}/{}", config.subject_dir(), question.name());
             let answer = super::ask_yes_or_no()?;
             if matches!(answer, Yes) {
                 question.delete_directories(config)?;
@@ -7,6 +7,7 @@ pub use range::Range;
 pub use time_info::TimeInfo;
 
 use chrono::{Datelike, Timelike};
+use std::time::Duration;
 
 pub fn delete_directory(name: &str) -> Result<(), std::io::Error> {
     std::process::Command::new("rm")
@@ -29,6 +30,16 @@ pub fn timestamp() -> String {
     )
 }
 
+/// Convert a number of seconds from a .toml file into a [`Duration`], returning [`None`] unless
+/// it is positive and small enough to fit in one
+pub fn duration_from_secs(seconds: f64) -> Option<Duration> {
+    if seconds > 0.0 {
+        Duration::try_from_secs_f64(seconds).ok()
+    } else {
+        None
+    }
+}
+
 pub fn seconds_to_hours_and_minutes(mut seconds: u64) -> (u64, u64, u64) {
     let hours = seconds / 3600;
     seconds %= 3600;
@@ -45,4 +56,14 @@ mod tests {
     fn timestamp_test() {
         println!("{}", timestamp());
     }
+
+    #[test]
+    fn durations() {
+        assert_eq!(duration_from_secs(0.5), Some(Duration::from_millis(500)));
+        assert_eq!(duration_from_secs(0.0), None);
+        assert_eq!(duration_from_secs(-1.0), None);
+        assert_eq!(duration_from_secs(f64::NAN), None);
+        assert_eq!(duration_from_secs(f64::INFINITY), None);
+        assert_eq!(duration_from_secs(1e30), None);
+    }
 }
@@ -1,9 +1,11 @@
 //! A simpler version of std::process::Output
 //!
-//! [`ProgramOutput`] is a simpler version of std::process::Output, reduced to just three components:
+//! [`ProgramOutput`] is a simpler version of std::process::Output, reduced to just five components:
 //! * The return code of the process
+//! * The signal that killed the process, if it crashed
 //! * STDOUT of the process
 //! * STDERR of the process
+//! * The files the process was expected to write, if any
 //!
 //! It implements [`PartialEq`], and is used to compare the results of an exam submission against the
 //! expected answer, and to produce trace files.
@@ -46,55 +48,115 @@
 //! }
 //! ```
 
+use std::collections::BTreeMap;
 use std::fmt;
+use std::os::unix::process::ExitStatusExt;
 use std::process::Output;
 
+/// The status a shell reports for a process killed by a signal is this plus the signal number
+const SIGNAL_STATUS_BASE: i32 = 128;
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct ProgramOutput {
     status: i32,
+    signal: Option<i32>,
     stdout: String,
     stderr: String,
+    /// The contents of each file the process was expected to write, or [`None`] if it did not
+    files: BTreeMap<String, Option<String>>,
 }
 
 impl ProgramOutput {
+    /// Build a [`ProgramOutput`] from an [`Output`]. A process killed by a signal is given the
+    /// status a shell would report for it, 128 plus the signal number.
     pub fn new(output: Output) -> Self {
+        let signal = output.status.signal();
+        let status = match (output.status.code(), signal) {
+            (Some(code), _) => code,
+            (None, Some(signal)) => SIGNAL_STATUS_BASE + signal,
+            (None, None) => -1,
+        };
         Self {
-            status: output.status.code().unwrap(),
-            stdout: String::from_utf8(output.stdout.as_slice().to_vec()).unwrap(),
-            stderr: String::from_utf8(output.stderr.as_slice().to_vec()).unwrap(),
+            status,
+            signal,
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            files: BTreeMap::new(),
         }
     }
 
     pub fn from_strings(status: i32, stdout: String, stderr: String) -> Self {
         Self {
             status,
+            signal: None,
             stdout,
             stderr,
+            files: BTreeMap::new(),
         }
     }
 
     pub fn code(&self) -> i32 {
         self.status
     }
+    /// The signal that killed the process, if it crashed
+    pub fn signal(&self) -> Option<i32> {
+        self.signal
+    }
     pub fn stdout(&self) -> &str {
         &self.stdout
     }
     pub fn stderr(&self) -> &str {
         &self.stderr
     }
+    /// The contents of the file `name`, if the process wrote it
+    pub fn file(&self, name: &str) -> Option<&str> {
+        self.files
+            .get(name)
+            .and_then(|contents| contents.as_deref())
+    }
+    pub fn add_file(&mut self, name: &str, contents: Option<String>) {
+        self.files.insert(name.to_string(), contents);
+    }
 
     pub fn combine(self, other: ProgramOutput) -> Self {
         let status = self.status.max(other.status);
+        let signal = self.signal.or(other.signal);
         let stdout = self.stdout + &other.stdout;
         let stderr = self.stderr + &other.stderr;
+        let mut files = self.files;
+        files.extend(other.files);
         Self {
             status,
+            signal,
             stdout,
             stderr,
+            files,
         }
     }
 }
 
+/// Describe a signal the way a shell reports a process it killed, e.g. "Segmentation fault
+/// (SIGSEGV)"
+pub fn signal_description(signal: i32) -> String {
+    let (description, name) = match signal {
+        1 => ("Hangup", "SIGHUP"),
+        2 => ("Interrupt", "SIGINT"),
+        3 => ("Quit", "SIGQUIT"),
+        4 => ("Illegal instruction", "SIGILL"),
+        5 => ("Trace/breakpoint trap", "SIGTRAP"),
+        6 => ("Abort", "SIGABRT"),
+        7 => ("Bus error", "SIGBUS"),
+        8 => ("Floating point exception", "SIGFPE"),
+        9 => ("Killed", "SIGKILL"),
+        11 => ("Segmentation fault", "SIGSEGV"),
+        13 => ("Broken pipe", "SIGPIPE"),
+        14 => ("Alarm clock", "SIGALRM"),
+        15 => ("Terminated", "SIGTERM"),
+        _ => return format!("Killed by signal {}", signal),
+    };
+    format!("{} ({})", description, name)
+}
+
 pub fn join_outputs(outputs: Vec<ProgramOutput>) -> (String, String) {
     let mut stdout = String::new();
     let mut stderr = String::new();
@@ -107,7 +169,10 @@ pub fn join_outputs(outputs: Vec<ProgramOutput>) -> (String, String) {
 
 impl fmt::Display for ProgramOutput {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "Exit Code: {}", self.status)?;
+        match self.signal {
+            Some(signal) => writeln!(f, "Crashed: {}", signal_description(signal))?,
+            None => writeln!(f, "Exit Code: {}", self.status)?,
+        }
         writeln!(f, "Stdout: {}", self.stdout)?;
         writeln!(f, "Stderr: {}", self.stderr)
     }
@@ -138,6 +203,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_signal() -> Result<(), Error> {
+        let output: ProgramOutput = Command::new("sh")
+            .arg("-c")
+            .arg("echo before; kill -SEGV $$")
+            .output()?
+            .into();
+        assert_eq!(output.signal(), Some(11));
+        assert_eq!(output.code(), 139);
+        assert_eq!(output.stdout(), "before\n");
+        assert!(output
+            .to_string()
+            .starts_with("Crashed: Segmentation fault (SIGSEGV)\n"));
+        assert_eq!(signal_description(7), "Bus error (SIGBUS)");
+        assert_eq!(signal_description(6), "Abort (SIGABRT)");
+        assert_eq!(signal_description(64), "Killed by signal 64");
+        Ok(())
+    }
+
     #[test]
     fn test_display() -> Result<(), Error> {
         let output1: ProgramOutput = Command::new("echo")
@@ -0,0 +1,13 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+int add(int a, int b);
+
+int main(int argc, char **argv) {
+    if (argc != 3) {
+        fprintf(stderr, "usage: add a b\n");
+        return 1;
+    }
+    printf("%d\n", add(atoi(argv[1]), atoi(argv[2])));
+    return 0;
+}
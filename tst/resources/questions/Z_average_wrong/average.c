@@ -0,0 +1,15 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+int main(int argc, char **argv) {
+    double sum = 0;
+
+    if (argc < 2) {
+        printf("\n");
+        return 0;
+    }
+    for (int i = 1; i < argc; i++)
+        sum += atof(argv[i]);
+    printf("%f\n", sum / (argc - 1));
+    return 0;
+}
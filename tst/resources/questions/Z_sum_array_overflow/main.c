@@ -0,0 +1,17 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+int sum_array(int *nums, int count);
+
+int main(int argc, char **argv)
+{
+	int *nums = malloc(sizeof(int) * (argc - 1));
+
+	if (nums == NULL)
+		return 1;
+	for (int i = 1; i < argc; i++)
+		nums[i - 1] = atoi(argv[i]);
+	printf("%d\n", sum_array(nums, argc - 1));
+	free(nums);
+	return 0;
+}
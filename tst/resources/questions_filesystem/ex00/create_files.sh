@@ -0,0 +1,6 @@
+#!/bin/sh
+rm -f old
+echo Z > z
+mkdir dir
+chmod 715 dir
+ln -s z link
@@ -0,0 +1,15 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+char *join_args(int count, char **words);
+
+int main(int argc, char **argv)
+{
+	char *joined = join_args(argc - 1, argv + 1);
+
+	if (joined == NULL)
+		return 1;
+	printf("%s\n", joined);
+	free(joined);
+	return 0;
+}
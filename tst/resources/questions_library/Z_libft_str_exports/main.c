@@ -0,0 +1,14 @@
+#include <stddef.h>
+#include <stdio.h>
+
+size_t	ft_strlen(const char *str);
+char	*ft_strcpy(char *dest, const char *src);
+
+int	main(int argc, char **argv)
+{
+	char	buffer[64];
+
+	if (argc == 2)
+		printf("%zu %s\n", ft_strlen(argv[1]), ft_strcpy(buffer, argv[1]));
+	return (0);
+}
@@ -0,0 +1,11 @@
+#include <stddef.h>
+#include <stdio.h>
+
+size_t	ft_strlen(const char *str);
+
+int	main(int argc, char **argv)
+{
+	if (argc == 2)
+		printf("%zu\n", ft_strlen(argv[1]));
+	return (0);
+}
@@ -0,0 +1,8 @@
+#!/usr/bin/env python3
+# Prints between 1 and 5 integers from -50 to 50, one per line, from the seed given
+import random
+import sys
+
+random.seed(int(sys.argv[1]))
+for _ in range(random.randint(1, 5)):
+    print(random.randint(-50, 50))
@@ -0,0 +1,21 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+int	main(int argc, char **argv)
+{
+	int	max;
+	int	i;
+
+	if (argc < 2)
+		return (0);
+	max = atoi(argv[1]);
+	i = 2;
+	while (i < argc)
+	{
+		if (atoi(argv[i]) > max)
+			max = atoi(argv[i]);
+		i++;
+	}
+	printf("%d\n", max);
+	return (0);
+}
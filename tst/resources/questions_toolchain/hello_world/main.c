@@ -0,0 +1,6 @@
+void hello_world(void);
+
+int main(void) {
+    hello_world();
+    return 0;
+}
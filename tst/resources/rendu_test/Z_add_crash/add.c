@@ -0,0 +1,8 @@
+#include <stddef.h>
+
+int add(int a, int b) {
+    int *sum = NULL;
+
+    *sum = a + b;
+    return *sum;
+}
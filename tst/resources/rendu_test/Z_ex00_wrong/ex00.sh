@@ -0,0 +1,4 @@
+rm old
+echo Z > z
+mkdir dir
+ln -s Z link
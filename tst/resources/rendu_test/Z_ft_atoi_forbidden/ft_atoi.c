@@ -0,0 +1,6 @@
+#include <stdlib.h>
+
+int ft_atoi(const char *str)
+{
+	return (atoi(str));
+}
@@ -0,0 +1,13 @@
+int ft_atoi(const char *str)
+{
+	int result = 0;
+
+	while (*str == ' ' || (*str >= '\t' && *str <= '\r'))
+		str++;
+	while (*str >= '0' && *str <= '9')
+	{
+		result = result * 10 + (*str - '0');
+		str++;
+	}
+	return result;
+}
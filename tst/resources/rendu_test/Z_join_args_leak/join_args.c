@@ -0,0 +1,52 @@
+#include <stdlib.h>
+
+static int	ft_strlen(char *str)
+{
+	int	len = 0;
+
+	while (str[len])
+		len++;
+	return (len);
+}
+
+static char	*ft_strdup(char *str)
+{
+	int		len = ft_strlen(str);
+	char	*copy;
+
+	copy = malloc(len + 1);
+	if (copy == NULL)
+		return (NULL);
+	for (int i = 0; i <= len; i++)
+		copy[i] = str[i];
+	return (copy);
+}
+
+char	*join_args(int count, char **words)
+{
+	char	*copy;
+	char	*joined;
+	int		total = 0;
+	int		end = 0;
+
+	for (int i = 0; i < count; i++)
+	{
+		copy = ft_strdup(words[i]);
+		if (copy == NULL)
+			return (NULL);
+		total += ft_strlen(copy) + 1;
+	}
+	joined = malloc(total + 1);
+	if (joined == NULL)
+		return (NULL);
+	end = 0;
+	for (int i = 0; i < count; i++)
+	{
+		if (i > 0)
+			joined[end++] = ' ';
+		for (int j = 0; words[i][j]; j++)
+			joined[end++] = words[i][j];
+	}
+	joined[end] = '\0';
+	return (joined);
+}
@@ -0,0 +1,18 @@
+void	copy_char(char *dest, const char *src, int i)
+{
+	dest[i] = src[i];
+}
+
+char	*ft_strcpy(char *dest, const char *src)
+{
+	int	i;
+
+	i = 0;
+	while (src[i])
+	{
+		copy_char(dest, src, i);
+		i++;
+	}
+	dest[i] = '\0';
+	return (dest);
+}
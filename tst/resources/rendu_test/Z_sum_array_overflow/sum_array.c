@@ -0,0 +1,12 @@
+int	sum_array(int *nums, int count)
+{
+	int	sum = 0;
+	int	i = 0;
+
+	while (nums[i] != 0 && i < count)
+	{
+		sum += nums[i];
+		i++;
+	}
+	return (sum);
+}
@@ -0,0 +1,13 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+int main(int argc, char **argv) {
+    double sum = 0;
+
+    for (int i = 1; i < argc; i++)
+        sum += atof(argv[i]);
+    if (argc > 1)
+        printf("%.4f", sum / (argc - 1));
+    printf("\n");
+    return 0;
+}
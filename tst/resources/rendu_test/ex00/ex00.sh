@@ -0,0 +1,4 @@
+rm old
+printf 'Z\n' > z
+mkdir dir && chmod u=rwx,g=x,o=rx dir
+ln -s z link
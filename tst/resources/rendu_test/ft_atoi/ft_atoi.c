@@ -0,0 +1,20 @@
+int ft_atoi(const char *str)
+{
+	long result = 0;
+	int sign = 1;
+
+	while (*str == ' ' || (*str >= '\t' && *str <= '\r'))
+		str++;
+	if (*str == '-' || *str == '+')
+	{
+		if (*str == '-')
+			sign = -1;
+		str++;
+	}
+	while (*str >= '0' && *str <= '9')
+	{
+		result = result * 10 + (*str - '0');
+		str++;
+	}
+	return (int)(result * sign);
+}
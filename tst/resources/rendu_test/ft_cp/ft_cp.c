@@ -0,0 +1,25 @@
+#include <fcntl.h>
+#include <unistd.h>
+
+int	main(int argc, char **argv)
+{
+	char	buffer[4096];
+	ssize_t	size;
+	int		in;
+	int		out;
+
+	if (argc != 3)
+	{
+		write(2, "Usage: ft_cp source destination\n", 32);
+		return (1);
+	}
+	in = open(argv[1], O_RDONLY);
+	out = open(argv[2], O_WRONLY | O_CREAT | O_TRUNC, 0644);
+	if (in < 0 || out < 0)
+		return (1);
+	while ((size = read(in, buffer, sizeof(buffer))) > 0)
+		write(out, buffer, size);
+	close(in);
+	close(out);
+	return (0);
+}
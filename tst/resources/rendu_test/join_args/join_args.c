@@ -0,0 +1,43 @@
+#include <stdlib.h>
+
+static int	ft_strlen(char *str)
+{
+	int	len = 0;
+
+	while (str[len])
+		len++;
+	return (len);
+}
+
+char	*join_args(int count, char **words)
+{
+	int		*lengths;
+	int		total = 0;
+	int		i;
+	char	*joined;
+	char	*end;
+
+	lengths = malloc(sizeof(int) * (count + 1));
+	if (lengths == NULL)
+		return (NULL);
+	for (i = 0; i < count; i++)
+	{
+		lengths[i] = ft_strlen(words[i]);
+		total += lengths[i] + 1;
+	}
+	joined = malloc(total + 1);
+	if (joined != NULL)
+	{
+		end = joined;
+		for (i = 0; i < count; i++)
+		{
+			if (i > 0)
+				*end++ = ' ';
+			for (int j = 0; j < lengths[i]; j++)
+				*end++ = words[i][j];
+		}
+		*end = '\0';
+	}
+	free(lengths);
+	return (joined);
+}
@@ -0,0 +1,29 @@
+#include <unistd.h>
+
+static void print_reversed(char *line, int len)
+{
+	while (len > 0)
+		write(1, &line[--len], 1);
+	write(1, "\n", 1);
+}
+
+int main(void)
+{
+	char line[4096];
+	char c;
+	int len = 0;
+
+	while (read(0, &c, 1) == 1)
+	{
+		if (c == '\n')
+		{
+			print_reversed(line, len);
+			len = 0;
+		}
+		else if (len < 4096)
+			line[len++] = c;
+	}
+	if (len > 0)
+		print_reversed(line, len);
+	return (0);
+}
@@ -0,0 +1,8 @@
+int	sum_array(int *nums, int count)
+{
+	int	sum = 0;
+
+	for (int i = 0; i < count; i++)
+		sum += nums[i];
+	return (sum);
+}